@@ -0,0 +1,76 @@
+use anyhow::{anyhow, Context, Result};
+use std::{env, fs, io, io::Read};
+
+use aoc_2023::day01;
+use aoc_2023::solver::{explain, solve_reader};
+
+const USAGE: &str = "Usage: solve <day> <part> [input file] [--explain | --annotate]";
+
+fn read_input(file: Option<&String>) -> Result<String> {
+    Ok(match file {
+        Some(file) => {
+            fs::read_to_string(file).with_context(|| format!("Could not read {}", file))?
+        }
+        None => {
+            let mut input = String::new();
+            io::stdin().read_to_string(&mut input)?;
+            input
+        }
+    })
+}
+
+// Reads the puzzle input from stdin if no input file is given.
+fn main() -> Result<()> {
+    let (flags, args): (Vec<_>, Vec<_>) = env::args().skip(1).partition(|a| a.starts_with("--"));
+    if let Some(flag) = flags
+        .iter()
+        .find(|f| *f != "--explain" && *f != "--annotate")
+    {
+        return Err(anyhow!("Unknown flag {}", flag)).context(USAGE);
+    }
+    if flags.len() > 1 {
+        return Err(anyhow!("Use either --explain or --annotate")).context(USAGE);
+    }
+    if args.len() > 3 {
+        return Err(anyhow!("Too many arguments")).context(USAGE);
+    }
+
+    let day = args.first().context(USAGE)?.parse::<u32>().context(USAGE)?;
+    let part = args.get(1).context(USAGE)?.parse::<u32>().context(USAGE)?;
+    if part != 1 && part != 2 {
+        return Err(anyhow!("There is no part {}", part)).context(USAGE);
+    }
+
+    if flags.iter().any(|f| f == "--explain") {
+        let input = read_input(args.get(2))?;
+        println!("{}", explain(day, part, &input)?);
+        return Ok(());
+    }
+
+    if flags.iter().any(|f| f == "--annotate") {
+        if day != 1 {
+            return Err(anyhow!("Only day 1 can be annotated"));
+        }
+
+        let vocabulary = match part {
+            1 => day01::DigitVocabulary::digits(),
+            2 => day01::DigitVocabulary::english(),
+            _ => unreachable!("The part was checked above"),
+        };
+        for line in read_input(args.get(2))?.lines().filter(|l| !l.is_empty()) {
+            println!("{}\n", day01::annotate(line, &vocabulary));
+        }
+        return Ok(());
+    }
+
+    let result = match args.get(2) {
+        Some(file) => {
+            let file = fs::File::open(file).with_context(|| format!("Could not open {}", file))?;
+            solve_reader(day, part, io::BufReader::new(file))?
+        }
+        None => solve_reader(day, part, io::stdin().lock())?,
+    };
+
+    println!("{}", result);
+    Ok(())
+}
@@ -0,0 +1,74 @@
+use anyhow::{Context, Result};
+use std::{env, fs, io};
+
+use aoc_2023::simulation::{asciicast, run, Simulation, Viewer};
+use aoc_2023::{day14, day16, day20, day21, day22};
+
+const USAGE: &str = "Usage: viewer <day> <input file> [--infinite] [--record <cast file> [frames]]";
+
+fn view<S: Simulation + Clone>(mut sim: S, record: &Option<(String, usize)>) -> Result<()> {
+    if let Some((file, frames)) = record {
+        let mut output = fs::File::create(file)?;
+        let count = asciicast::record(&mut sim, *frames, 0.1, &mut output)?;
+        println!("Recorded {} frames to {}", count, file);
+        return Ok(());
+    }
+
+    let mut viewer = Viewer::new(sim);
+    run(&mut viewer, io::stdin().lock(), &mut io::stdout())
+}
+
+fn main() -> Result<()> {
+    let args = env::args().skip(1).collect::<Vec<_>>();
+    let day = args.first().context(USAGE)?.parse::<u32>()?;
+    let file = args.get(1).context(USAGE)?;
+    let input = fs::read_to_string(file).with_context(|| format!("Could not read {}", file))?;
+
+    let mut infinite = false;
+    let mut record = None;
+    let mut rest = args.iter().skip(2).peekable();
+    while let Some(arg) = rest.next() {
+        match arg.as_str() {
+            "--infinite" => infinite = true,
+            "--record" => {
+                let cast = rest.next().context(USAGE)?.to_string();
+                let frames = match rest.next_if(|f| !f.starts_with("--")) {
+                    Some(f) => f.parse::<usize>().context(USAGE)?,
+                    None => 100,
+                };
+                record = Some((cast, frames));
+            }
+            _ => Err(anyhow::anyhow!(USAGE))?,
+        }
+    }
+    if infinite && day != 21 {
+        return Err(anyhow::anyhow!("Only the garden of day 21 can be infinite"));
+    }
+
+    match day {
+        14 => view(
+            day14::SpinSimulation::new(&day14::input_generator(&input)?),
+            &record,
+        ),
+        16 => view(
+            day16::BeamSimulation::new(
+                &day16::input_generator(&input)?,
+                day16::Beam::new((0, 0), day16::Direction::Right),
+            ),
+            &record,
+        ),
+        20 => view(
+            day20::PulseSimulation::new(&day20::input_generator(&input)?),
+            &record,
+        ),
+        21 => view(
+            day21::GardenSimulation::new(&day21::input_generator(&input)?, infinite)?,
+            &record,
+        ),
+        22 => view(
+            day22::FallSimulation::new(&day22::input_generator(&input)?),
+            &record,
+        ),
+        _ => Err(anyhow::anyhow!("Day {} has no simulation", day)),
+    }
+}
@@ -1,45 +1,137 @@
+mod vocabulary;
+
 use anyhow::{Context, Result};
+use std::{cmp::Reverse, io::BufRead};
+
+pub use vocabulary::{DigitVocabulary, WordMatch};
 
-use crate::utils::AocError::*;
-
-fn normalise_digit(s: &str) -> &str {
-    match s {
-        "one" => "1",
-        "two" => "2",
-        "three" => "3",
-        "four" => "4",
-        "five" => "5",
-        "six" => "6",
-        "seven" => "7",
-        "eight" => "8",
-        "nine" => "9",
-        v => v,
+use crate::{
+    explain::Explanation,
+    utils::{read_lines, AocError::*},
+};
+
+fn first_digit(value: u32) -> u32 {
+    let mut value = value;
+    while value >= 10 {
+        value /= 10;
     }
+    value
 }
 
-fn from_str(s: &str, patterns: &[&str]) -> Result<u32> {
-    let first_p = patterns
-        .iter()
-        .enumerate()
-        .filter_map(|(i, n)| s.find(n).map(|p| (i, p)))
-        .min_by(|a, b| a.1.cmp(&b.1))
+/// Combine the first digit of the first number and the last digit of the
+/// last number in the line. If two words match at the same position, the
+/// longer one wins.
+pub fn calibration_value(s: &str, vocabulary: &DigitVocabulary) -> Result<u32> {
+    let mut matches = vocabulary.find_all(s);
+    let m = matches
+        .next()
         .ok_or(GenericError)
-        .context("Could not determine min value, it seems there are no numbers")?;
-    let first = normalise_digit(patterns[first_p.0]);
+        .context("Could not determine calibration value, it seems there are no numbers")?;
+
+    let (first, last) = matches.fold((m, m), |(first, last), m| {
+        let first = if (m.start, Reverse(m.end)) < (first.start, Reverse(first.end)) {
+            m
+        } else {
+            first
+        };
+        let last = if (m.start, m.end) > (last.start, last.end) {
+            m
+        } else {
+            last
+        };
+        (first, last)
+    });
+
+    Ok(first_digit(first.value) * 10 + last.value % 10)
+}
+
+pub fn calibrate(input: &[String], vocabulary: &DigitVocabulary) -> Result<u32> {
+    Ok(input
+        .iter()
+        .map(|l| calibration_value(l, vocabulary))
+        .collect::<Result<Vec<_>>>()?
+        .iter()
+        .sum::<u32>())
+}
+
+/// A number found in a line. `start` and `end` are byte offsets,
+/// `overlaps_with` holds the indices of the other matches of the same line
+/// that share characters with this one, like "two" and "one" in "twone".
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Match {
+    pub start: usize,
+    pub end: usize,
+    pub value: u32,
+    pub overlaps_with: Vec<usize>,
+}
+
+/// All numbers in the line ordered by their position.
+pub fn digit_matches(line: &str, vocabulary: &DigitVocabulary) -> Vec<Match> {
+    let mut found = vocabulary.find_all(line).collect::<Vec<_>>();
+    found.sort_by_key(|m| (m.start, m.end));
 
-    let s_rev = s.chars().rev().collect::<String>();
-    let last_p = patterns
+    found
         .iter()
-        .map(|n| n.chars().rev().collect::<String>())
         .enumerate()
-        .filter_map(|(i, n)| s_rev.find(&n).map(|p| (i, p)))
-        .min_by(|a, b| a.1.cmp(&b.1))
-        .ok_or(GenericError)
-        .context("Could not determine min value, it seems there are no numbers")?;
-    let last = normalise_digit(patterns[last_p.0]);
+        .map(|(i, m)| {
+            let overlaps_with = found
+                .iter()
+                .enumerate()
+                .filter(|(j, o)| i != *j && o.start < m.end && m.start < o.end)
+                .map(|(j, _)| j)
+                .collect();
 
-    let value = format!("{}{}", first, last).parse::<u32>()?;
-    Ok(value)
+            Match {
+                start: m.start,
+                end: m.end,
+                value: m.value,
+                overlaps_with,
+            }
+        })
+        .collect()
+}
+
+/// Print the line with its calibration value followed by one line per match
+/// that points at the matched word, e.g.
+///
+/// ```text
+/// xtwone3 => 23
+///  two     2  overlaps one
+///    one   1  overlaps two
+///       3  3
+/// ```
+pub fn annotate(line: &str, vocabulary: &DigitVocabulary) -> String {
+    let value = calibration_value(line, vocabulary)
+        .map(|v| v.to_string())
+        .unwrap_or("no numbers".to_string());
+    let width = line.chars().count();
+    let matches = digit_matches(line, vocabulary);
+
+    let mut lines = vec![format!("{} => {}", line, value)];
+    for m in &matches {
+        let indent = line[..m.start].chars().count();
+        let word = &line[m.start..m.end];
+        let pad = width - indent - word.chars().count();
+
+        let mut annotation = format!(
+            "{}{}{}  {}",
+            " ".repeat(indent),
+            word,
+            " ".repeat(pad),
+            m.value
+        );
+        if !m.overlaps_with.is_empty() {
+            let others = m
+                .overlaps_with
+                .iter()
+                .map(|i| &line[matches[*i].start..matches[*i].end])
+                .collect::<Vec<_>>();
+            annotation += &format!("  overlaps {}", others.join(", "));
+        }
+        lines.push(annotation);
+    }
+
+    lines.join("\n")
 }
 
 #[aoc_generator(day01)]
@@ -51,37 +143,62 @@ pub fn input_generator(input: &str) -> Result<Vec<String>> {
         .collect::<Vec<_>>())
 }
 
+pub fn input_reader<R: BufRead>(reader: R) -> impl Iterator<Item = Result<String>> {
+    read_lines(reader, |l| Ok(l.to_owned()))
+}
+
 #[aoc(day01, part1)]
 pub fn solve_part1(input: &[String]) -> Result<u32> {
-    let patterns = ["0", "1", "2", "3", "4", "5", "6", "7", "8", "9"];
-    let max = input
-        .iter()
-        .map(|l| from_str(l, &patterns))
-        .collect::<Result<Vec<_>>>()?
-        .iter()
-        .sum::<u32>();
-    Ok(max)
+    calibrate(input, &DigitVocabulary::digits())
 }
 
 #[aoc(day01, part2)]
 pub fn solve_part2(input: &[String]) -> Result<u32> {
-    let patterns = [
-        "0", "1", "2", "3", "4", "5", "6", "7", "8", "9", "one", "two", "three", "four", "five",
-        "six", "seven", "eight", "nine",
-    ];
-    let max = input
+    calibrate(input, &DigitVocabulary::english())
+}
+
+pub fn explain(input: &[String], vocabulary: &DigitVocabulary) -> Result<Explanation> {
+    let values = input
         .iter()
-        .map(|l| from_str(l, &patterns))
-        .collect::<Result<Vec<_>>>()?
+        .map(|l| calibration_value(l, vocabulary))
+        .collect::<Result<Vec<_>>>()?;
+    let rows = input
         .iter()
-        .sum::<u32>();
+        .zip(values.iter())
+        .map(|(l, v)| vec![l.clone(), v.to_string()])
+        .collect();
+
+    Ok(Explanation::new(
+        &["line", "value"],
+        rows,
+        values.iter().sum::<u32>(),
+    ))
+}
+
+pub fn explain_part1(input: &[String]) -> Result<Explanation> {
+    explain(input, &DigitVocabulary::digits())
+}
+
+pub fn explain_part2(input: &[String]) -> Result<Explanation> {
+    explain(input, &DigitVocabulary::english())
+}
 
-    Ok(max)
+pub fn stream<R: BufRead>(reader: R, vocabulary: &DigitVocabulary) -> Result<u32> {
+    input_reader(reader).try_fold(0, |acc, l| Ok(acc + calibration_value(&l?, vocabulary)?))
+}
+
+pub fn stream_part1<R: BufRead>(reader: R) -> Result<u32> {
+    stream(reader, &DigitVocabulary::digits())
+}
+
+pub fn stream_part2<R: BufRead>(reader: R) -> Result<u32> {
+    stream(reader, &DigitVocabulary::english())
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
+    use std::str::FromStr;
 
     fn sample() -> &'static str {
         "two13nine
@@ -108,4 +225,60 @@ zoneight234
         let data = input()?;
         Ok(assert_eq!(281, solve_part2(&data)?))
     }
+
+    #[test]
+    fn part2_stream() -> Result<()> {
+        Ok(assert_eq!(281, stream_part2(sample().as_bytes())?))
+    }
+
+    #[test]
+    fn multilingual() -> Result<()> {
+        let mut vocabulary = DigitVocabulary::german();
+        vocabulary.extend(&DigitVocabulary::english())?;
+
+        let data = input_generator("xzweiundvierzig\nseven2acht")?;
+        Ok(assert_eq!(24 + 78, calibrate(&data, &vocabulary)?))
+    }
+
+    #[test]
+    fn custom_vocabulary() -> Result<()> {
+        let vocabulary = DigitVocabulary::from_str(
+            "# numbers above nine
+            ten=10
+            twelve = 12
+            seven=7
+            seventeen=17",
+        )?;
+
+        assert_eq!(12, calibration_value("twelve", &vocabulary)?);
+        assert_eq!(17, calibration_value("xtenseventeen", &vocabulary)?);
+        Ok(assert!(DigitVocabulary::from_str("ten=x").is_err()))
+    }
+
+    #[test]
+    fn overlapping_words() {
+        let vocabulary = DigitVocabulary::english();
+        let values = vocabulary
+            .find_all("oneightwo")
+            .map(|m| (m.start, m.value))
+            .collect::<Vec<_>>();
+        assert_eq!(vec![(0, 1), (2, 8), (6, 2)], values);
+    }
+
+    #[test]
+    fn annotate_overlaps() {
+        let vocabulary = DigitVocabulary::english();
+        let matches = digit_matches("eightwo3", &vocabulary);
+        assert_eq!(vec![1], matches[0].overlaps_with);
+        assert_eq!(vec![0], matches[1].overlaps_with);
+        assert!(matches[2].overlaps_with.is_empty());
+
+        assert_eq!(
+            "xtwone3 => 23
+ two     2  overlaps one
+   one   1  overlaps two
+      3  3",
+            annotate("xtwone3", &vocabulary)
+        );
+    }
 }
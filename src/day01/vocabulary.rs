@@ -0,0 +1,168 @@
+use aho_corasick::AhoCorasick;
+use anyhow::{Context, Error, Result};
+use std::{fs, path::Path, str::FromStr};
+
+use crate::utils::AocError::*;
+
+const DIGITS: [&str; 10] = ["0", "1", "2", "3", "4", "5", "6", "7", "8", "9"];
+
+/// The words that are recognized as numbers in a calibration line together
+/// with their value. A word may stand for a number with more than one digit,
+/// e.g. "twelve".
+#[derive(Debug, Clone)]
+pub struct DigitVocabulary {
+    words: Vec<(String, u32)>,
+    // Finds all words in a single pass over a line, built once per vocabulary.
+    matcher: AhoCorasick,
+}
+
+/// A word of the vocabulary found in a line. `start` and `end` are byte
+/// offsets.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WordMatch {
+    pub start: usize,
+    pub end: usize,
+    pub value: u32,
+}
+
+impl FromStr for DigitVocabulary {
+    type Err = Error;
+
+    // Parse one `word=value` mapping per line. Empty lines and lines starting
+    // with '#' are ignored. Digits are not added implicitly.
+    fn from_str(s: &str) -> Result<Self> {
+        let words = s
+            .lines()
+            .map(|l| l.trim())
+            .filter(|l| !l.is_empty() && !l.starts_with('#'))
+            .map(|l| {
+                let mut split = l.split('=');
+                let word = split
+                    .next()
+                    .map(|w| w.trim())
+                    .filter(|w| !w.is_empty())
+                    .ok_or(GenericError)
+                    .with_context(|| format!("Could not read word in '{}'", l))?;
+                let value = split
+                    .next()
+                    .ok_or(GenericError)
+                    .with_context(|| format!("Could not read value in '{}'", l))?
+                    .trim()
+                    .parse::<u32>()?;
+
+                Ok((word.to_string(), value))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Self::build(words)
+    }
+}
+
+impl DigitVocabulary {
+    pub fn new(words: &[(&str, u32)]) -> Result<Self> {
+        Self::build(words.iter().map(|(w, v)| (w.to_string(), *v)).collect())
+    }
+
+    fn build(words: Vec<(String, u32)>) -> Result<Self> {
+        let matcher = AhoCorasick::new(words.iter().map(|(w, _)| w))
+            .context("Could not build matcher for vocabulary")?;
+        Ok(DigitVocabulary { words, matcher })
+    }
+
+    // The built-in word lists are short and always valid.
+    fn builtin_words(words: Vec<(String, u32)>) -> Self {
+        Self::build(words).expect("Built-in vocabulary is invalid")
+    }
+
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        fs::read_to_string(path)
+            .with_context(|| format!("Could not read vocabulary {}", path.display()))?
+            .parse()
+    }
+
+    /// Only the digits 0 to 9.
+    pub fn digits() -> Self {
+        Self::builtin_words(Self::digit_words().collect())
+    }
+
+    fn digit_words() -> impl Iterator<Item = (String, u32)> {
+        DIGITS
+            .iter()
+            .enumerate()
+            .map(|(i, d)| (d.to_string(), i as u32))
+    }
+
+    fn digits_and(words: [&str; 9]) -> Self {
+        let spelled = words
+            .iter()
+            .enumerate()
+            .map(|(i, w)| (w.to_string(), i as u32 + 1));
+        Self::builtin_words(Self::digit_words().chain(spelled).collect())
+    }
+
+    /// Digits and the spelled out numbers "one" to "nine".
+    pub fn english() -> Self {
+        Self::digits_and([
+            "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
+        ])
+    }
+
+    pub fn german() -> Self {
+        Self::digits_and([
+            "eins", "zwei", "drei", "vier", "fünf", "sechs", "sieben", "acht", "neun",
+        ])
+    }
+
+    pub fn french() -> Self {
+        Self::digits_and([
+            "un", "deux", "trois", "quatre", "cinq", "six", "sept", "huit", "neuf",
+        ])
+    }
+
+    pub fn spanish() -> Self {
+        Self::digits_and([
+            "uno", "dos", "tres", "cuatro", "cinco", "seis", "siete", "ocho", "nueve",
+        ])
+    }
+
+    /// Look up a built-in vocabulary by its language code.
+    pub fn builtin(name: &str) -> Option<Self> {
+        Some(match name {
+            "digits" => Self::digits(),
+            "en" => Self::english(),
+            "de" => Self::german(),
+            "fr" => Self::french(),
+            "es" => Self::spanish(),
+            _ => None?,
+        })
+    }
+
+    /// Add the words of another vocabulary, e.g. to combine two languages.
+    /// Words that are already known keep their original value.
+    pub fn extend(&mut self, other: &DigitVocabulary) -> Result<()> {
+        let mut words = self.words.clone();
+        for (word, value) in &other.words {
+            if !words.iter().any(|(w, _)| w == word) {
+                words.push((word.clone(), *value));
+            }
+        }
+
+        *self = Self::build(words)?;
+        Ok(())
+    }
+
+    pub fn words(&self) -> impl Iterator<Item = (&str, u32)> {
+        self.words.iter().map(|(w, v)| (w.as_str(), *v))
+    }
+
+    /// All occurrences of all words in the line, including overlapping
+    /// ones like "one" and "eight" in "oneight", in a single scan.
+    pub fn find_all<'a>(&'a self, line: &'a str) -> impl Iterator<Item = WordMatch> + 'a {
+        self.matcher.find_overlapping_iter(line).map(|m| WordMatch {
+            start: m.start(),
+            end: m.end(),
+            value: self.words[m.pattern().as_usize()].1,
+        })
+    }
+}
@@ -1,67 +1,95 @@
 use anyhow::{Context, Error, Result};
-use std::{cmp::max, str::FromStr};
+use std::{cmp::max, collections::BTreeMap, io::BufRead, str::FromStr};
 
-use crate::utils::AocError::*;
+use crate::{
+    explain::Explanation,
+    utils::{read_lines, AocError::*},
+};
 
-use Cube::*;
+const PALETTE: [&str; 3] = ["red", "green", "blue"];
 
-#[derive(Debug)]
-pub enum Cube {
-    Red(u32),
-    Green(u32),
-    Blue(u32),
-}
-
-impl FromStr for Cube {
-    type Err = Error;
-
-    // Parse '3 blue' or '13 red'
-    fn from_str(s: &str) -> Result<Self> {
-        let mut split = s.trim().split(' ').map(|s| s.trim());
-        let num = split
-            .next()
-            .map(|s| s.parse::<u32>())
-            .ok_or(GenericError)
-            .context("Could not parse input, expected number")??;
-        let what = split
-            .next()
-            .ok_or(GenericError)
-            .context("Could not parse input, expected color")?;
+// Parse '3 blue' or '13 red'
+fn parse_cube(s: &str) -> Result<(String, u32)> {
+    let mut split = s.trim().splitn(2, ' ').map(|s| s.trim());
+    let num = split
+        .next()
+        .map(|s| s.parse::<u32>())
+        .ok_or(GenericError)
+        .context("Could not parse input, expected number")??;
+    let color = split
+        .next()
+        .filter(|s| !s.is_empty())
+        .ok_or(GenericError)
+        .context("Could not parse input, expected color")?;
 
-        Ok(match what {
-            "blue" => Blue(num),
-            "red" => Red(num),
-            "green" => Green(num),
-            _ => Err(GenericError).context("Unknown color")?,
-        })
-    }
+    Ok((color.to_string(), num))
 }
 
-#[derive(Debug)]
-pub struct Draw {
-    cubes: Vec<Cube>,
+/// A number of cubes per color. Used for the content of the bag as well as
+/// for the cubes revealed in a single draw.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Bag {
+    cubes: BTreeMap<String, u32>,
 }
 
-impl FromStr for Draw {
+pub type Draw = Bag;
+
+impl FromStr for Bag {
     type Err = Error;
 
     // Parse '3 red, 5 blue, 1 green'
     fn from_str(s: &str) -> Result<Self> {
-        let cubes = s
-            .split(',')
-            .map(Cube::from_str)
-            .collect::<Result<Vec<_>>>()?;
-        Ok(Self { cubes })
+        let mut bag = Bag::default();
+        for cube in s.split(',').filter(|c| !c.trim().is_empty()) {
+            let (color, num) = parse_cube(cube)?;
+            *bag.cubes.entry(color).or_insert(0) += num;
+        }
+        Ok(bag)
     }
 }
 
-impl Draw {
-    fn is_valid_for_part1(&self) -> bool {
-        self.cubes.iter().all(|e| match e {
-            Red(v) => *v <= 12,
-            Green(v) => *v <= 13,
-            Blue(v) => *v <= 14,
-        })
+impl Bag {
+    pub fn new(cubes: &[(&str, u32)]) -> Self {
+        let cubes = cubes.iter().map(|(c, n)| (c.to_string(), *n)).collect();
+        Bag { cubes }
+    }
+
+    /// The bag from part 1 with 12 red, 13 green and 14 blue cubes.
+    pub fn part1() -> Self {
+        Self::new(&[("red", 12), ("green", 13), ("blue", 14)])
+    }
+
+    pub fn count(&self, color: &str) -> u32 {
+        self.cubes.get(color).copied().unwrap_or(0)
+    }
+
+    pub fn colors(&self) -> impl Iterator<Item = &str> {
+        self.cubes.keys().map(|c| c.as_str())
+    }
+
+    /// Whether all the given cubes can be taken out of this bag at once.
+    pub fn contains(&self, other: &Bag) -> bool {
+        other.cubes.iter().all(|(c, n)| *n <= self.count(c))
+    }
+
+    /// The smallest bag that contains both bags.
+    pub fn union(&self, other: &Bag) -> Bag {
+        let mut result = self.clone();
+        for (color, num) in &other.cubes {
+            let entry = result.cubes.entry(color.clone()).or_insert(0);
+            *entry = max(*entry, *num);
+        }
+        result
+    }
+
+    pub fn total(&self) -> u32 {
+        self.cubes.values().sum()
+    }
+
+    /// The product of the number of cubes per color. Colors of the palette
+    /// that are not in the bag count as zero.
+    pub fn power(&self, palette: &[&str]) -> u32 {
+        palette.iter().map(|c| self.count(c)).product()
     }
 }
 
@@ -101,26 +129,25 @@ impl FromStr for Game {
 }
 
 impl Game {
-    pub fn is_valid_for_part1(&self) -> bool {
-        self.draws.iter().map(|v| v.is_valid_for_part1()).all(|b| b)
-    }
-
-    pub fn power(&self) -> u32 {
-        let mut max_red = 0;
-        let mut max_blue = 0;
-        let mut max_green = 0;
-
-        for set in &self.draws {
-            for cube in &set.cubes {
-                match cube {
-                    Red(v) => max_red = max(max_red, *v),
-                    Green(v) => max_green = max(max_green, *v),
-                    Blue(v) => max_blue = max(max_blue, *v),
-                }
-            }
-        }
+    pub fn is_possible_with(&self, bag: &Bag) -> bool {
+        self.draws.iter().all(|d| bag.contains(d))
+    }
+
+    pub fn id(&self) -> u32 {
+        self.id
+    }
 
-        max_red * max_green * max_blue
+    /// The power of the minimal bag, see `Bag::power`.
+    pub fn power(&self, palette: &[&str]) -> u32 {
+        self.minimal_bag().power(palette)
+    }
+
+    /// The bag with the fewest cubes of every color this game is possible
+    /// with.
+    pub fn minimal_bag(&self) -> Bag {
+        self.draws
+            .iter()
+            .fold(Bag::default(), |acc, draw| acc.union(draw))
     }
 }
 
@@ -133,22 +160,109 @@ pub fn input_generator(input: &str) -> Result<Vec<Game>> {
         .collect::<Result<Vec<_>>>()
 }
 
-#[aoc(day02, part1)]
-pub fn solve_part1(input: &[Game]) -> Result<u32> {
-    let hash = input
+pub fn input_reader<R: BufRead>(reader: R) -> impl Iterator<Item = Result<Game>> {
+    read_lines(reader, Game::from_str)
+}
+
+/// Sum of the ids of all games that are possible with the given bag.
+pub fn possible_games(input: &[Game], bag: &Bag) -> u32 {
+    input
         .iter()
-        .map(|g| (g.id, g.is_valid_for_part1()))
-        .filter(|(_, v)| *v)
-        .map(|(i, _)| i)
-        .sum::<u32>();
+        .filter(|g| g.is_possible_with(bag))
+        .map(|g| g.id)
+        .sum::<u32>()
+}
 
-    Ok(hash)
+/// For every bag the ids of the games that are possible with it.
+pub fn admitted_games(input: &[Game], bags: &[Bag]) -> Vec<Vec<u32>> {
+    bags.iter()
+        .map(|bag| {
+            input
+                .iter()
+                .filter(|g| g.is_possible_with(bag))
+                .map(|g| g.id)
+                .collect()
+        })
+        .collect()
+}
+
+/// The bag with the fewest cubes in total that makes all of the given games
+/// possible. Every color has to hold as many cubes as the most demanding
+/// game needs, so this is the union of the minimal bags of those games.
+pub fn smallest_bag_for(input: &[Game], ids: &[u32]) -> Result<Bag> {
+    ids.iter().try_fold(Bag::default(), |acc, id| {
+        let game = input
+            .iter()
+            .find(|g| g.id == *id)
+            .ok_or(GenericError)
+            .with_context(|| format!("Unknown game {}", id))?;
+        Ok(acc.union(&game.minimal_bag()))
+    })
+}
+
+pub fn total_power(input: &[Game], palette: &[&str]) -> u32 {
+    input.iter().map(|g| g.power(palette)).sum::<u32>()
+}
+
+#[aoc(day02, part1)]
+pub fn solve_part1(input: &[Game]) -> Result<u32> {
+    Ok(possible_games(input, &Bag::part1()))
 }
 
 #[aoc(day02, part2)]
 pub fn solve_part2(input: &[Game]) -> Result<u32> {
-    let result = input.iter().map(|g| g.power()).sum::<u32>();
-    Ok(result)
+    Ok(total_power(input, &PALETTE))
+}
+
+pub fn explain_part1(input: &[Game]) -> Result<Explanation> {
+    let bag = Bag::part1();
+    let rows = input
+        .iter()
+        .map(|g| vec![g.id.to_string(), g.is_possible_with(&bag).to_string()])
+        .collect();
+    Ok(Explanation::new(
+        &["game", "possible"],
+        rows,
+        possible_games(input, &bag),
+    ))
+}
+
+pub fn explain_part2(input: &[Game]) -> Result<Explanation> {
+    let rows = input
+        .iter()
+        .map(|g| {
+            let minimum = g.minimal_bag();
+            let mut row = vec![g.id.to_string()];
+            row.extend(PALETTE.iter().map(|c| minimum.count(c).to_string()));
+            row.push(g.power(&PALETTE).to_string());
+            row
+        })
+        .collect();
+
+    let mut columns = vec!["game"];
+    columns.extend(PALETTE);
+    columns.push("power");
+    Ok(Explanation::new(
+        &columns,
+        rows,
+        total_power(input, &PALETTE),
+    ))
+}
+
+pub fn stream_part1<R: BufRead>(reader: R) -> Result<u32> {
+    let bag = Bag::part1();
+    input_reader(reader).try_fold(0, |acc, g| {
+        let g = g?;
+        Ok(if g.is_possible_with(&bag) {
+            acc + g.id
+        } else {
+            acc
+        })
+    })
+}
+
+pub fn stream_part2<R: BufRead>(reader: R) -> Result<u32> {
+    input_reader(reader).try_fold(0, |acc, g| Ok(acc + g?.power(&PALETTE)))
 }
 
 #[cfg(test)]
@@ -178,4 +292,50 @@ Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green"
         let data = input()?;
         Ok(assert_eq!(2286, solve_part2(&data)?))
     }
+
+    #[test]
+    fn part2_explain() -> Result<()> {
+        let data = input()?;
+        let explanation = explain_part2(&data)?;
+        assert_eq!("2286", explanation.answer);
+        Ok(assert_eq!(
+            vec!["3", "20", "13", "6", "1560"],
+            explanation.rows[2]
+        ))
+    }
+
+    #[test]
+    fn part1_stream() -> Result<()> {
+        Ok(assert_eq!(8, stream_part1(sample().as_bytes())?))
+    }
+
+    #[test]
+    fn other_colors() -> Result<()> {
+        let data = input_generator(
+            "Game 1: 3 dark violet, 2 teal; 4 teal
+Game 2: 1 dark violet, 5 teal, 1 red",
+        )?;
+        let bag = Bag::from_str("3 dark violet, 4 teal")?;
+
+        assert_eq!(1, possible_games(&data, &bag));
+        Ok(assert_eq!(
+            12 + 5,
+            total_power(&data, &["dark violet", "teal"])
+        ))
+    }
+
+    #[test]
+    fn bag_queries() -> Result<()> {
+        let data = input()?;
+        let bags = [Bag::part1(), Bag::from_str("6 red, 3 green, 6 blue")?];
+        assert_eq!(
+            vec![vec![1, 2, 5], vec![1, 2, 5]],
+            admitted_games(&data, &bags)
+        );
+
+        let smallest = smallest_bag_for(&data, &[1, 4])?;
+        assert_eq!(Bag::from_str("14 red, 3 green, 15 blue")?, smallest);
+        assert_eq!(32, smallest.total());
+        Ok(assert!(smallest_bag_for(&data, &[6]).is_err()))
+    }
 }
@@ -0,0 +1,67 @@
+use super::Schematic;
+
+/// How the values of the numbers around a gear are combined.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Combine {
+    Product,
+    Sum,
+    Min,
+    Max,
+}
+
+impl Combine {
+    fn apply<I: Iterator<Item = u64>>(&self, values: I) -> u64 {
+        match self {
+            Combine::Product => values.product(),
+            Combine::Sum => values.sum(),
+            Combine::Min => values.min().unwrap_or(0),
+            Combine::Max => values.max().unwrap_or(0),
+        }
+    }
+}
+
+/// Which symbols count as gears: any of `symbols` touching exactly `arity`
+/// numbers. The numbers of each gear are combined into its ratio.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GearRule {
+    pub symbols: Vec<char>,
+    pub arity: usize,
+    pub combine: Combine,
+}
+
+impl GearRule {
+    pub fn new(symbols: &str, arity: usize, combine: Combine) -> Self {
+        GearRule {
+            symbols: symbols.chars().collect(),
+            arity,
+            combine,
+        }
+    }
+
+    /// The rule from part 2, a '*' with exactly two numbers multiplied.
+    pub fn part2() -> Self {
+        Self::new("*", 2, Combine::Product)
+    }
+
+    /// Indices of the symbols in the schematic that are gears.
+    pub fn gears<'a>(&'a self, schematic: &'a Schematic) -> impl Iterator<Item = usize> + 'a {
+        schematic
+            .symbols_with_parts(self.arity)
+            .filter(|s| self.symbols.contains(&schematic.symbols[*s].kind))
+    }
+
+    pub fn ratio(&self, schematic: &Schematic, gear: usize) -> u64 {
+        let values = schematic
+            .numbers_of(gear)
+            .iter()
+            .map(|n| schematic.numbers[*n].value as u64);
+        self.combine.apply(values)
+    }
+
+    /// Sum of the ratios of all gears in the schematic.
+    pub fn evaluate(&self, schematic: &Schematic) -> u64 {
+        self.gears(schematic)
+            .map(|g| self.ratio(schematic, g))
+            .sum()
+    }
+}
@@ -1,89 +1,84 @@
-use anyhow::Result;
-use std::{
-    cmp::{max, min},
-    collections::HashMap,
-};
+mod gear;
+mod schematic;
 
-type Map = Vec<Vec<char>>;
+use anyhow::Result;
+use std::str::FromStr;
 
-#[aoc_generator(day03)]
-pub fn input_generator(input: &str) -> Result<Map> {
-    Ok(input
-        .lines()
-        .filter(|s| !s.is_empty())
-        .map(|l| l.chars().collect::<Vec<_>>())
-        .collect::<Vec<_>>())
-}
+use crate::explain::Explanation;
 
-fn find_gears(
-    input: &Map,
-    is_valid: fn(char) -> bool,
-) -> Result<HashMap<(usize, usize), Vec<u32>>> {
-    let mut map = HashMap::new();
-
-    for y in 0..input.len() {
-        let mut buf = vec![];
-        let mut start = 0;
-        let mut in_number = false;
-        for x in 0..input[y].len() {
-            if !input[y][x].is_ascii_digit() || x == input[y].len() - 1 {
-                if !in_number {
-                    continue;
-                }
-
-                if input[y][x].is_ascii_digit() && x == input[y].len() - 1 {
-                    buf.push(input[y][x]);
-                }
-
-                let num = buf.iter().collect::<String>().parse::<u32>()?;
-                for ys in (max(1, y) - 1)..=min(input.len() - 1, y + 1) {
-                    for xs in (max(1, start) - 1)..=min(input[y].len() - 1, x) {
-                        if is_valid(input[ys][xs]) {
-                            map.entry((ys, xs))
-                                .and_modify(|v: &mut Vec<u32>| v.push(num))
-                                .or_insert(vec![num]);
-                        }
-                    }
-                }
-
-                buf = vec![];
-                in_number = false;
-            } else {
-                if !in_number {
-                    start = x;
-                }
-                in_number = true;
-                buf.push(input[y][x]);
-            }
-        }
-    }
+pub use gear::{Combine, GearRule};
+pub use schematic::{PartNumber, Schematic, Symbol};
 
-    Ok(map)
+#[aoc_generator(day03)]
+pub fn input_generator(input: &str) -> Result<Schematic> {
+    Schematic::from_str(input)
 }
 
 #[aoc(day03, part1)]
-pub fn solve_part1(input: &Map) -> Result<u32> {
-    let map = find_gears(input, |c| !c.is_ascii_digit() && c != '.')?;
-    let result = map.values().map(|v| v.iter().sum::<u32>()).sum::<u32>();
+pub fn solve_part1(input: &Schematic) -> Result<u32> {
+    let result = input.part_numbers().map(|n| n.value).sum::<u32>();
 
     Ok(result)
 }
 
 #[aoc(day03, part2)]
-pub fn solve_part2(input: &Map) -> Result<u32> {
-    let map = find_gears(input, |c| c == '*')?;
-    let result = map
-        .values()
-        .filter_map(|v| {
-            if v.len() == 2 {
-                Some(v[0] * v[1])
-            } else {
-                None
-            }
+pub fn solve_part2(input: &Schematic) -> Result<u64> {
+    Ok(GearRule::part2().evaluate(input))
+}
+
+pub fn explain_part1(input: &Schematic) -> Result<Explanation> {
+    let rows = input
+        .numbers
+        .iter()
+        .enumerate()
+        .map(|(i, n)| {
+            let symbols = input
+                .symbols_of(i)
+                .iter()
+                .map(|s| input.symbols[*s].kind)
+                .collect::<String>();
+            let value = if symbols.is_empty() { 0 } else { n.value };
+            vec![
+                n.value.to_string(),
+                (n.row + 1).to_string(),
+                format!("{}-{}", n.col_span.start + 1, n.col_span.end),
+                symbols,
+                value.to_string(),
+            ]
         })
-        .sum::<u32>();
+        .collect();
 
-    Ok(result)
+    Ok(Explanation::new(
+        &["number", "row", "columns", "symbols", "value"],
+        rows,
+        solve_part1(input)?,
+    ))
+}
+
+pub fn explain_part2(input: &Schematic) -> Result<Explanation> {
+    let rule = GearRule::part2();
+    let rows = rule
+        .gears(input)
+        .map(|g| {
+            let (row, col) = input.symbols[g].pos;
+            let numbers = input
+                .numbers_of(g)
+                .iter()
+                .map(|n| input.numbers[*n].value.to_string())
+                .collect::<Vec<_>>();
+            vec![
+                format!("{},{}", row + 1, col + 1),
+                numbers.join(" * "),
+                rule.ratio(input, g).to_string(),
+            ]
+        })
+        .collect();
+
+    Ok(Explanation::new(
+        &["gear", "numbers", "ratio"],
+        rows,
+        rule.evaluate(input),
+    ))
 }
 
 #[cfg(test)]
@@ -103,7 +98,7 @@ mod test {
 .664.598.."
     }
 
-    fn input() -> Result<Map> {
+    fn input() -> Result<Schematic> {
         input_generator(sample())
     }
 
@@ -118,4 +113,84 @@ mod test {
         let data = input()?;
         Ok(assert_eq!(467835, solve_part2(&data)?))
     }
+
+    #[test]
+    fn part2_explain() -> Result<()> {
+        let data = input()?;
+        let explanation = explain_part2(&data)?;
+        assert_eq!("467835", explanation.answer);
+        Ok(assert_eq!(
+            vec!["2,4", "467 * 35", "16345"],
+            explanation.rows[0]
+        ))
+    }
+
+    #[test]
+    fn unattached_numbers() -> Result<()> {
+        let data = input()?;
+        let unattached = data.unattached_numbers().collect::<Vec<_>>();
+        assert_eq!(2, unattached.len());
+        assert_eq!(114, unattached[0].value);
+        Ok(assert_eq!(
+            &PartNumber {
+                value: 58,
+                row: 5,
+                col_span: 7..9
+            },
+            unattached[1]
+        ))
+    }
+
+    #[test]
+    fn no_double_counting() -> Result<()> {
+        // 12 touches the '*' with both digits and the '#' as well
+        let data = input_generator(
+            "12#
+*..",
+        )?;
+        assert_eq!(vec![0, 1], data.symbols_of(0));
+        assert_eq!(12, solve_part1(&data)?);
+        Ok(assert_eq!(2, data.symbols_with_parts(1).count()))
+    }
+
+    #[test]
+    fn components() -> Result<()> {
+        let data = input_generator(
+            "1*2..5
+..3...
+.4$..6
+....#.",
+        )?;
+        let values = |number| {
+            data.component(number)
+                .iter()
+                .map(|n| data.numbers[*n].value)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(vec![1, 2, 3, 4], values(4));
+        assert_eq!(vec![5], values(2));
+        Ok(assert_eq!(vec![6], values(5)))
+    }
+
+    #[test]
+    fn gear_rules() -> Result<()> {
+        let data = input_generator(
+            "1.2.3
+.#.*.
+4...5
+..$..
+..6..",
+        )?;
+        assert_eq!(
+            1 + 2 + 4,
+            GearRule::new("#", 3, Combine::Sum).evaluate(&data)
+        );
+        assert_eq!(0, GearRule::part2().evaluate(&data));
+        assert_eq!(4 + 5, GearRule::new("#*", 3, Combine::Max).evaluate(&data));
+        assert_eq!(6, GearRule::new("$", 1, Combine::Min).evaluate(&data));
+        Ok(assert_eq!(
+            2 * 4 + 2 * 3 * 5,
+            GearRule::new("#*$", 3, Combine::Product).evaluate(&data)
+        ))
+    }
 }
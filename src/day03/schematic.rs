@@ -0,0 +1,171 @@
+use anyhow::{Error, Result};
+use std::{
+    cmp::min,
+    collections::{HashMap, VecDeque},
+    ops::Range,
+    str::FromStr,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PartNumber {
+    pub value: u32,
+    pub row: usize,
+    pub col_span: Range<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Symbol {
+    pub kind: char,
+    /// (row, column)
+    pub pos: (usize, usize),
+}
+
+/// The engine schematic as a bipartite graph between the numbers and the
+/// symbols they touch. Each pair is connected at most once, no matter how
+/// many cells of the number touch the symbol.
+#[derive(Debug, Clone)]
+pub struct Schematic {
+    pub numbers: Vec<PartNumber>,
+    pub symbols: Vec<Symbol>,
+    number_edges: Vec<Vec<usize>>,
+    symbol_edges: Vec<Vec<usize>>,
+}
+
+impl FromStr for Schematic {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let grid = s
+            .lines()
+            .filter(|s| !s.is_empty())
+            .map(|l| l.chars().collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+
+        let mut numbers = vec![];
+        let mut symbols = vec![];
+        for (row, line) in grid.iter().enumerate() {
+            let mut col = 0;
+            while col < line.len() {
+                let c = line[col];
+                if c.is_ascii_digit() {
+                    let start = col;
+                    while col < line.len() && line[col].is_ascii_digit() {
+                        col += 1;
+                    }
+                    let value = line[start..col].iter().collect::<String>().parse::<u32>()?;
+                    numbers.push(PartNumber {
+                        value,
+                        row,
+                        col_span: start..col,
+                    });
+                    continue;
+                }
+
+                if c != '.' {
+                    symbols.push(Symbol {
+                        kind: c,
+                        pos: (row, col),
+                    });
+                }
+                col += 1;
+            }
+        }
+
+        Ok(Self::new(numbers, symbols, &grid))
+    }
+}
+
+impl Schematic {
+    fn new(numbers: Vec<PartNumber>, symbols: Vec<Symbol>, grid: &[Vec<char>]) -> Self {
+        let by_pos = symbols
+            .iter()
+            .enumerate()
+            .map(|(i, s)| (s.pos, i))
+            .collect::<HashMap<_, _>>();
+
+        let mut number_edges = vec![vec![]; numbers.len()];
+        let mut symbol_edges = vec![vec![]; symbols.len()];
+        for (i, n) in numbers.iter().enumerate() {
+            let width = grid[n.row].len();
+            for row in n.row.saturating_sub(1)..=min(grid.len() - 1, n.row + 1) {
+                for col in n.col_span.start.saturating_sub(1)..min(width, n.col_span.end + 1) {
+                    if let Some(s) = by_pos.get(&(row, col)) {
+                        number_edges[i].push(*s);
+                        symbol_edges[*s].push(i);
+                    }
+                }
+            }
+        }
+
+        Schematic {
+            numbers,
+            symbols,
+            number_edges,
+            symbol_edges,
+        }
+    }
+
+    /// Indices of the symbols touching the number with the given index.
+    pub fn symbols_of(&self, number: usize) -> &[usize] {
+        &self.number_edges[number]
+    }
+
+    /// Indices of the numbers touching the symbol with the given index.
+    pub fn numbers_of(&self, symbol: usize) -> &[usize] {
+        &self.symbol_edges[symbol]
+    }
+
+    /// Numbers that touch at least one symbol.
+    pub fn part_numbers(&self) -> impl Iterator<Item = &PartNumber> {
+        self.numbers
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| !self.number_edges[*i].is_empty())
+            .map(|(_, n)| n)
+    }
+
+    /// Numbers that do not touch any symbol.
+    pub fn unattached_numbers(&self) -> impl Iterator<Item = &PartNumber> {
+        self.numbers
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| self.number_edges[*i].is_empty())
+            .map(|(_, n)| n)
+    }
+
+    /// Indices of the symbols that touch exactly `count` numbers.
+    pub fn symbols_with_parts(&self, count: usize) -> impl Iterator<Item = usize> + '_ {
+        (0..self.symbols.len()).filter(move |s| self.symbol_edges[*s].len() == count)
+    }
+
+    /// Indices of all numbers that are connected to the given number through
+    /// shared symbols, including the number itself.
+    pub fn component(&self, number: usize) -> Vec<usize> {
+        let mut seen_numbers = vec![false; self.numbers.len()];
+        let mut seen_symbols = vec![false; self.symbols.len()];
+        let mut queue = VecDeque::from([number]);
+        seen_numbers[number] = true;
+
+        let mut result = vec![];
+        while let Some(n) = queue.pop_front() {
+            result.push(n);
+
+            for s in &self.number_edges[n] {
+                if seen_symbols[*s] {
+                    continue;
+                }
+                seen_symbols[*s] = true;
+
+                for next in &self.symbol_edges[*s] {
+                    if !seen_numbers[*next] {
+                        seen_numbers[*next] = true;
+                        queue.push_back(*next);
+                    }
+                }
+            }
+        }
+
+        result.sort();
+        result
+    }
+}
@@ -0,0 +1,113 @@
+use anyhow::{Context, Result};
+use std::collections::VecDeque;
+
+use crate::utils::AocError::*;
+
+/// Which cards a card with `wins` matching numbers gives copies of.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CopyRule {
+    /// One copy of each of the next `wins` cards, the rule of the puzzle.
+    /// Copies past the end of the deck are lost.
+    Next,
+    /// One copy of each of the previous `wins` cards. Copies before the
+    /// start of the deck are lost.
+    Previous,
+    /// Like `Next` but continue at the start of the deck. A card never wins
+    /// a copy of itself. The deck is only processed once, so copies of cards
+    /// that were already scratched are counted but do not win anything.
+    WrapAround,
+    /// Like `Next` but the n-th next card is copied `weights[n - 1]` times.
+    /// Cards without a weight are copied once.
+    Weighted(Vec<usize>),
+}
+
+impl CopyRule {
+    fn weight(&self, distance: usize) -> usize {
+        match self {
+            CopyRule::Weighted(weights) => weights.get(distance - 1).copied().unwrap_or(1),
+            _ => 1,
+        }
+    }
+
+    // The cards that get copies and how many per instance of the card.
+    fn targets(&self, card: usize, wins: usize, len: usize) -> Vec<(usize, usize)> {
+        match self {
+            CopyRule::Next | CopyRule::Weighted(_) => (1..=wins)
+                .map(|d| (card + d, self.weight(d)))
+                .take_while(|(t, _)| *t < len)
+                .collect(),
+            CopyRule::Previous => (1..=wins.min(card)).map(|d| (card - d, 1)).collect(),
+            CopyRule::WrapAround => (1..=wins.min(len - 1))
+                .map(|d| ((card + d) % len, 1))
+                .collect(),
+        }
+    }
+}
+
+/// Plays out the scratchcards: every instance of a card wins copies of
+/// other cards according to the copy rule, which are scratched in turn.
+#[derive(Debug, Clone)]
+pub struct CardCascade {
+    rule: CopyRule,
+}
+
+impl CardCascade {
+    pub fn new(rule: CopyRule) -> Self {
+        CardCascade { rule }
+    }
+
+    /// The number of instances of every card, given the wins per card.
+    pub fn copies(&self, wins: &[usize]) -> Vec<usize> {
+        let len = wins.len();
+        let mut copies = vec![1_usize; len];
+
+        // Copies only go backwards with the previous rule, so scratching the
+        // cards from the bottom up finishes every card before it is used.
+        let order: Box<dyn Iterator<Item = usize>> = match self.rule {
+            CopyRule::Previous => Box::new((0..len).rev()),
+            _ => Box::new(0..len),
+        };
+        for i in order {
+            for (target, weight) in self.rule.targets(i, wins[i], len) {
+                copies[target] += copies[i] * weight;
+            }
+        }
+
+        copies
+    }
+
+    pub fn total(&self, wins: &[usize]) -> usize {
+        self.copies(wins).iter().sum()
+    }
+
+    /// The total number of cards, reading the wins card by card. Only the
+    /// copies won for upcoming cards are kept, so memory is bounded by the
+    /// number of wins on a card. Rules that copy cards above the current one
+    /// need the whole deck and are not supported.
+    pub fn stream<I: Iterator<Item = Result<usize>>>(&self, wins: I) -> Result<usize> {
+        if matches!(self.rule, CopyRule::Previous | CopyRule::WrapAround) {
+            return Err(GenericError)
+                .with_context(|| format!("Can not stream cards with rule {:?}", self.rule));
+        }
+
+        let mut pending = VecDeque::new();
+        let mut total = 0;
+
+        for wins in wins {
+            let copies = 1 + pending.pop_front().unwrap_or(0);
+            total += copies;
+
+            let wins = wins?;
+            if pending.len() < wins {
+                pending.resize(wins, 0);
+            }
+            pending
+                .iter_mut()
+                .take(wins)
+                .enumerate()
+                .for_each(|(d, p)| *p += copies * self.rule.weight(d + 1));
+        }
+
+        Ok(total)
+    }
+}
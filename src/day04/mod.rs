@@ -1,13 +1,20 @@
+mod cascade;
+
 use anyhow::{Context, Error, Result};
-use num::checked_pow;
-use std::{cmp::min, str::FromStr};
+use num::{BigUint, Zero};
+use std::{collections::HashSet, io::BufRead, str::FromStr};
+
+use crate::{
+    explain::Explanation,
+    utils::{read_lines, AocError::*},
+};
 
-use crate::utils::AocError::*;
+pub use cascade::{CardCascade, CopyRule};
 
 #[derive(Debug)]
 pub struct Card {
     winning: Vec<u32>,
-    numbers: Vec<u32>,
+    numbers: HashSet<u32>,
 }
 
 impl FromStr for Card {
@@ -36,28 +43,28 @@ impl FromStr for Card {
                 .ok_or(GenericError)
                 .context("Could not parse winners")?
                 .trim(),
-        )?;
+        )?
+        .into_iter()
+        .collect();
 
         Ok(Card { winning, numbers })
     }
 }
 
 impl Card {
-    fn wins(&self) -> usize {
+    pub fn wins(&self) -> usize {
         self.winning
             .iter()
-            .map(|w| if self.numbers.contains(w) { 1 } else { 0 })
-            .sum::<usize>()
+            .filter(|w| self.numbers.contains(w))
+            .count()
     }
 
-    fn score(&self) -> Result<u32> {
-        let exp = self.wins();
-        if exp == 0 {
-            Ok(0)
-        } else {
-            checked_pow(2, exp - 1)
-                .ok_or(GenericError)
-                .context("Could not pow")
+    /// Doubles with every win, so it is unbounded to support cards with any
+    /// number of winning numbers.
+    fn score(&self) -> BigUint {
+        match self.wins() {
+            0 => BigUint::zero(),
+            wins => BigUint::from(1_u32) << (wins - 1),
         }
     }
 }
@@ -80,25 +87,64 @@ pub fn input_generator(input: &str) -> Result<Vec<Card>> {
         .context("Error while parsing input")
 }
 
+pub fn input_reader<R: BufRead>(reader: R) -> impl Iterator<Item = Result<Card>> {
+    read_lines(reader, Card::from_str)
+}
+
 #[aoc(day04, part1)]
-pub fn solve_part1(input: &[Card]) -> Result<u32> {
-    Ok(input.iter().filter_map(|c| c.score().ok()).sum::<u32>())
+pub fn solve_part1(input: &[Card]) -> Result<BigUint> {
+    Ok(input.iter().map(|c| c.score()).sum::<BigUint>())
+}
+
+fn wins(input: &[Card]) -> Vec<usize> {
+    input.iter().map(|c| c.wins()).collect()
 }
 
 #[aoc(day04, part2)]
 pub fn solve_part2(input: &[Card]) -> Result<usize> {
-    let mut copies = vec![1_usize; input.len()];
+    Ok(CardCascade::new(CopyRule::Next).total(&wins(input)))
+}
 
-    for (i, c) in input.iter().enumerate() {
-        let wins = c.wins();
+pub fn explain_part1(input: &[Card]) -> Result<Explanation> {
+    let rows = input
+        .iter()
+        .enumerate()
+        .map(|(i, c)| {
+            vec![
+                (i + 1).to_string(),
+                c.wins().to_string(),
+                c.score().to_string(),
+            ]
+        })
+        .collect();
+    Ok(Explanation::new(
+        &["card", "wins", "score"],
+        rows,
+        solve_part1(input)?,
+    ))
+}
 
-        let end = min(input.len() - 1, i + wins);
-        for j in i + 1..=end {
-            copies[j] += copies[i];
-        }
-    }
+pub fn explain_part2(input: &[Card]) -> Result<Explanation> {
+    let copies = CardCascade::new(CopyRule::Next).copies(&wins(input));
+    let rows = input
+        .iter()
+        .zip(copies.iter())
+        .enumerate()
+        .map(|(i, (c, n))| vec![(i + 1).to_string(), c.wins().to_string(), n.to_string()])
+        .collect();
+    Ok(Explanation::new(
+        &["card", "wins", "copies"],
+        rows,
+        copies.iter().sum::<usize>(),
+    ))
+}
 
-    Ok(copies.iter().sum())
+pub fn stream_part1<R: BufRead>(reader: R) -> Result<BigUint> {
+    input_reader(reader).try_fold(BigUint::zero(), |acc, c| Ok(acc + c?.score()))
+}
+
+pub fn stream_part2<R: BufRead>(reader: R) -> Result<usize> {
+    CardCascade::new(CopyRule::Next).stream(input_reader(reader).map(|c| Ok(c?.wins())))
 }
 
 #[cfg(test)]
@@ -121,7 +167,24 @@ Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11"
     #[test]
     fn part1_sample() -> Result<()> {
         let data = input()?;
-        Ok(assert_eq!(13, solve_part1(&data)?))
+        Ok(assert_eq!(BigUint::from(13_u32), solve_part1(&data)?))
+    }
+
+    #[test]
+    fn many_wins() -> Result<()> {
+        let numbers = (1..=40)
+            .map(|n| n.to_string())
+            .collect::<Vec<_>>()
+            .join(" ");
+        let input = format!("Card 1: {} | {}\nCard 2: 1 | 1", numbers, numbers);
+        let expected: BigUint = (BigUint::from(1_u32) << 39_usize) + 1_u32;
+
+        assert_eq!(expected, solve_part1(&input_generator(&input)?)?);
+        assert_eq!(expected, stream_part1(input.as_bytes())?);
+        Ok(assert_eq!(
+            expected.to_string(),
+            explain_part1(&input_generator(&input)?)?.answer
+        ))
     }
 
     #[test]
@@ -129,4 +192,47 @@ Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11"
         let data = input()?;
         Ok(assert_eq!(30, solve_part2(&data)?))
     }
+
+    #[test]
+    fn part2_stream() -> Result<()> {
+        Ok(assert_eq!(30, stream_part2(sample().as_bytes())?))
+    }
+
+    #[test]
+    fn copy_rules() -> Result<()> {
+        let wins = wins(&input()?);
+        assert_eq!(
+            vec![1, 2, 4, 8, 14, 1],
+            CardCascade::new(CopyRule::Next).copies(&wins)
+        );
+        assert_eq!(
+            vec![1, 1, 1, 1, 1, 1],
+            CardCascade::new(CopyRule::Previous).copies(&[0; 6])
+        );
+        assert_eq!(
+            vec![5, 4, 2, 1],
+            CardCascade::new(CopyRule::Previous).copies(&[0, 1, 1, 2])
+        );
+        assert_eq!(
+            vec![2, 3, 1],
+            CardCascade::new(CopyRule::WrapAround).copies(&[1, 0, 5])
+        );
+        Ok(assert_eq!(
+            vec![1, 3, 2],
+            CardCascade::new(CopyRule::Weighted(vec![2])).copies(&[2, 0, 0])
+        ))
+    }
+
+    #[test]
+    fn stream_rules() -> Result<()> {
+        let wins = [2, 1, 0];
+        let weighted = CardCascade::new(CopyRule::Weighted(vec![3, 2]));
+        assert_eq!(
+            weighted.total(&wins),
+            weighted.stream(wins.iter().map(|w| Ok(*w)))?
+        );
+        Ok(assert!(CardCascade::new(CopyRule::WrapAround)
+            .stream(wins.iter().map(|w| Ok(*w)))
+            .is_err()))
+    }
 }
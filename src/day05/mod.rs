@@ -1,8 +1,15 @@
+mod piecewise;
+
 use anyhow::{Context, Error, Result};
-use rayon::{slice::ParallelSlice, iter::ParallelIterator};
-use std::str::FromStr;
+use std::{
+    cmp::{max, min},
+    fmt, ops,
+    str::FromStr,
+};
+
+use crate::{explain::Explanation, utils::AocError::*};
 
-use crate::utils::AocError::*;
+pub use piecewise::{PiecewiseMap, DOMAIN};
 
 #[derive(Debug)]
 pub struct Almanac {
@@ -22,6 +29,31 @@ impl FromStr for Almanac {
             .collect::<Result<Vec<_>>>()
             .context("Error while parsing input")?;
 
+        for pair in seed_map.windows(2) {
+            if pair[0].destination != pair[1].source {
+                return Err(GenericError).with_context(|| {
+                    format!(
+                        "{} map is followed by {} map",
+                        pair[0].name(),
+                        pair[1].name()
+                    )
+                });
+            }
+        }
+
+        for map in &seed_map {
+            if let Some(o) = map.overlaps().iter().find(|o| o.contradictory) {
+                return Err(GenericError).with_context(|| {
+                    format!(
+                        "Ranges {} and {} of {} map map the same values differently",
+                        o.first,
+                        o.second,
+                        map.name()
+                    )
+                });
+            }
+        }
+
         let seeds = s.lines().next()
             .ok_or(GenericError).context("Could not find line with seeds")?
             .split(": ")
@@ -36,6 +68,46 @@ impl FromStr for Almanac {
 }
 
 impl Almanac {
+    /// The names of all categories from seed to location in the order they
+    /// are mapped.
+    pub fn categories(&self) -> impl Iterator<Item = &str> {
+        self.seed_map
+            .first()
+            .map(|m| m.source.as_str())
+            .into_iter()
+            .chain(self.seed_map.iter().map(|m| m.destination.as_str()))
+    }
+
+    fn category(&self, name: &str) -> Result<usize> {
+        self.categories()
+            .position(|c| c == name)
+            .ok_or(GenericError)
+            .with_context(|| format!("Unknown category {}", name))
+    }
+
+    /// Map a value of one category to any later category, e.g. a seed to
+    /// its humidity.
+    pub fn map(&self, from: &str, to: &str, value: i128) -> Result<i128> {
+        let start = self.category(from)?;
+        let end = self.category(to)?;
+        if end < start {
+            return Err(GenericError)
+                .with_context(|| format!("Can not map {} back to {}", from, to));
+        }
+
+        Ok(self.seed_map[start..end]
+            .iter()
+            .fold(value, |v, m| m.map_seed(v).unwrap_or(v)))
+    }
+
+    /// All overlapping ranges of all maps together with the name of the map.
+    pub fn overlaps(&self) -> Vec<(String, Overlap)> {
+        self.seed_map
+            .iter()
+            .flat_map(|m| m.overlaps().into_iter().map(|o| (m.name(), o)))
+            .collect()
+    }
+
     pub fn map_seed(&self, seed: i128) -> i128 {
         let mut seed = seed;
 
@@ -47,30 +119,183 @@ impl Almanac {
 
         seed
     }
+
+    /// Map all seeds in the interval at once. Every piece of the result is
+    /// returned with the offset from the seeds to the mapped values.
+    pub fn map_interval(&self, interval: Interval) -> Vec<(Interval, i128)> {
+        let mut pieces = vec![(interval, 0)];
+
+        for map in &self.seed_map {
+            pieces = pieces
+                .into_iter()
+                .flat_map(|(piece, offset)| {
+                    map.split(piece)
+                        .into_iter()
+                        .map(move |(p, o)| (p.start + o..p.end + o, offset + o))
+                })
+                .collect();
+        }
+
+        pieces
+    }
+
+    /// The lowest location for any of the seeds in the intervals.
+    pub fn lowest_location(&self, seeds: &[Interval]) -> Option<Location> {
+        seeds
+            .iter()
+            .flat_map(|s| self.map_interval(s.clone()))
+            .filter(|(piece, _)| !piece.is_empty())
+            .map(|(piece, offset)| Location {
+                location: piece.start,
+                seed: piece.start - offset,
+            })
+            .min_by_key(|l| l.location)
+    }
+
+    /// The whole chain of maps as a single map from seed to location.
+    pub fn compose(&self) -> PiecewiseMap {
+        PiecewiseMap::new(
+            self.map_interval(DOMAIN)
+                .into_iter()
+                .map(|(p, o)| (p.start - o..p.end - o, o))
+                .collect(),
+        )
+    }
+
+    /// The seeds that are planted in any of the given locations.
+    pub fn seeds_for(&self, locations: &Interval) -> Vec<Interval> {
+        self.compose().preimage(locations)
+    }
+}
+
+/// Half-open interval of seeds or of mapped values.
+pub type Interval = ops::Range<i128>;
+
+/// A location together with the seed that is planted there.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Location {
+    pub location: i128,
+    pub seed: i128,
+}
+
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.location)
+    }
 }
 
 #[derive(Debug)]
 pub struct SeedMap {
+    source: String,
+    destination: String,
     ranges: Vec<Range>,
 }
 
+/// Two ranges of a map whose sources overlap. They contradict each other if
+/// they map the shared values to different destinations.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Overlap {
+    pub first: usize,
+    pub second: usize,
+    pub contradictory: bool,
+}
+
 impl FromStr for SeedMap {
     type Err = Error;
 
+    // Parse the header 'seed-to-soil map:' followed by the ranges
     fn from_str(s: &str) -> Result<Self> {
-        let ranges = s
-            .lines()
-            .skip(1)
+        let mut lines = s.lines();
+        let header = lines
+            .next()
+            .and_then(|l| l.trim().strip_suffix(" map:"))
+            .ok_or(GenericError)
+            .context("Could not find map header")?;
+        let (source, destination) = header
+            .split_once("-to-")
+            .ok_or(GenericError)
+            .with_context(|| format!("Could not read categories of {}", header))?;
+
+        let ranges = lines
+            .filter(|l| !l.trim().is_empty())
             .map(Range::from_str)
             .collect::<Result<Vec<_>>>()?;
-        Ok(SeedMap { ranges })
+        Ok(SeedMap {
+            source: source.to_string(),
+            destination: destination.to_string(),
+            ranges,
+        })
     }
 }
 
 impl SeedMap {
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    pub fn destination(&self) -> &str {
+        &self.destination
+    }
+
+    pub fn name(&self) -> String {
+        format!("{}-to-{}", self.source, self.destination)
+    }
+
+    pub fn overlaps(&self) -> Vec<Overlap> {
+        let mut result = vec![];
+        for (i, a) in self.ranges.iter().enumerate() {
+            for (j, b) in self.ranges.iter().enumerate().skip(i + 1) {
+                let (a_source, b_source) = (a.source(), b.source());
+                if max(a_source.start, b_source.start) < min(a_source.end, b_source.end) {
+                    result.push(Overlap {
+                        first: i,
+                        second: j,
+                        contradictory: a.offset() != b.offset(),
+                    });
+                }
+            }
+        }
+        result
+    }
+
     pub fn map_seed(&self, seed: i128) -> Option<i128> {
         self.ranges.iter().filter_map(|r| r.map(seed)).next()
     }
+
+    /// Split the interval into the pieces that are mapped by the same range
+    /// and return each with its offset. Seeds outside of all ranges keep
+    /// their value. Like `map_seed` the first matching range wins.
+    pub fn split(&self, interval: Interval) -> Vec<(Interval, i128)> {
+        let mut result = vec![];
+        let mut unmapped = vec![interval];
+
+        for range in &self.ranges {
+            let source = range.source();
+            let mut rest = vec![];
+
+            for piece in unmapped {
+                let start = max(piece.start, source.start);
+                let end = min(piece.end, source.end);
+                if start >= end {
+                    rest.push(piece);
+                    continue;
+                }
+
+                result.push((start..end, range.offset()));
+                if piece.start < start {
+                    rest.push(piece.start..start);
+                }
+                if end < piece.end {
+                    rest.push(end..piece.end);
+                }
+            }
+
+            unmapped = rest;
+        }
+
+        result.extend(unmapped.into_iter().map(|p| (p, 0)));
+        result
+    }
 }
 
 #[derive(Debug)]
@@ -104,6 +329,14 @@ impl Range {
             None
         }
     }
+
+    fn source(&self) -> Interval {
+        self.source_start..self.source_start + self.length
+    }
+
+    fn offset(&self) -> i128 {
+        self.destination_start - self.source_start
+    }
 }
 
 #[aoc_generator(day05)]
@@ -117,14 +350,63 @@ pub fn solve_part1(input: &Almanac) -> Result<i128> {
     Ok(*result.iter().min().ok_or(GenericError).context("Could not find lowest location")?)
 }
 
+fn seed_ranges(input: &Almanac) -> Vec<Interval> {
+    input
+        .seeds
+        .chunks_exact(2)
+        .map(|r| r[0]..r[0] + r[1])
+        .collect()
+}
+
 #[aoc(day05, part2)]
-pub fn solve_part2(input: &Almanac) -> Result<i128> {
-    let min = input.seeds.par_chunks_exact(2).filter_map(|r| {
-        let start = r[0];
-        let end = r[0] + r[1];
-        (start..end).map(|s| input.map_seed(s)).min()
-    }).min().ok_or(GenericError).context("Foo")?;
-    Ok(min)
+pub fn solve_part2(input: &Almanac) -> Result<Location> {
+    input
+        .lowest_location(&seed_ranges(input))
+        .ok_or(GenericError)
+        .context("Could not find lowest location")
+}
+
+pub fn explain_part1(input: &Almanac) -> Result<Explanation> {
+    let columns = input.categories().collect::<Vec<_>>();
+    let rows = input
+        .seeds
+        .iter()
+        .map(|s| {
+            let mut value = *s;
+            let mut row = vec![value.to_string()];
+            for map in &input.seed_map {
+                value = map.map_seed(value).unwrap_or(value);
+                row.push(value.to_string());
+            }
+            row
+        })
+        .collect();
+
+    Ok(Explanation::new(&columns, rows, solve_part1(input)?))
+}
+
+pub fn explain_part2(input: &Almanac) -> Result<Explanation> {
+    let rows = seed_ranges(input)
+        .into_iter()
+        .map(|r| {
+            let lowest = input.lowest_location(std::slice::from_ref(&r));
+            vec![
+                format!("{}-{}", r.start, r.end - 1),
+                lowest
+                    .map(|l| l.seed.to_string())
+                    .unwrap_or("-".to_string()),
+                lowest
+                    .map(|l| l.location.to_string())
+                    .unwrap_or("-".to_string()),
+            ]
+        })
+        .collect();
+
+    Ok(Explanation::new(
+        &["seeds", "seed", "lowest location"],
+        rows,
+        solve_part2(input)?,
+    ))
 }
 
 #[cfg(test)]
@@ -180,6 +462,121 @@ humidity-to-location map:
     #[test]
     fn part2_sample() -> Result<()> {
         let data = input()?;
-        Ok(assert_eq!(46, solve_part2(&data)?))
+        Ok(assert_eq!(46, solve_part2(&data)?.location))
+    }
+
+    #[test]
+    fn part2_seed() -> Result<()> {
+        let data = input()?;
+        let lowest = solve_part2(&data)?;
+        assert_eq!(82, lowest.seed);
+        Ok(assert_eq!(lowest.location, data.map_seed(lowest.seed)))
+    }
+
+    #[test]
+    fn part1_explain() -> Result<()> {
+        let data = input()?;
+        let explanation = explain_part1(&data)?;
+        assert_eq!("35", explanation.answer);
+        assert_eq!("location", explanation.columns[7]);
+        Ok(assert_eq!(
+            vec!["79", "81", "81", "81", "74", "78", "78", "82"],
+            explanation.rows[0]
+        ))
+    }
+
+    #[test]
+    fn composition() -> Result<()> {
+        let data = input()?;
+        let composed = data.compose();
+        for seed in 0..110 {
+            assert_eq!(Some(data.map_seed(seed)), composed.map(seed));
+        }
+
+        let inverse = composed.inverse()?;
+        assert_eq!(Some(82), inverse.map(46));
+        assert_eq!(Some(13), inverse.map(35));
+
+        let seeds = data.seeds_for(&(40..50));
+        for seed in 0..110 {
+            let planted = seeds.iter().any(|s| s.contains(&seed));
+            assert_eq!((40..50).contains(&data.map_seed(seed)), planted);
+        }
+        Ok(assert!(seeds.iter().any(|s| s.contains(&82))))
+    }
+
+    #[test]
+    fn categories() -> Result<()> {
+        let data = input()?;
+        assert_eq!(
+            vec![
+                "seed",
+                "soil",
+                "fertilizer",
+                "water",
+                "light",
+                "temperature",
+                "humidity",
+                "location"
+            ],
+            data.categories().collect::<Vec<_>>()
+        );
+        assert_eq!(81, data.map("seed", "soil", 79)?);
+        assert_eq!(78, data.map("seed", "humidity", 79)?);
+        assert_eq!(82, data.map("seed", "location", 79)?);
+        assert_eq!(79, data.map("seed", "seed", 79)?);
+        assert!(data.map("soil", "seed", 81).is_err());
+        Ok(assert!(data.map("seed", "sand", 79).is_err()))
+    }
+
+    #[test]
+    fn validation() -> Result<()> {
+        let broken = Almanac::from_str(
+            "seeds: 1
+
+seed-to-soil map:
+50 98 2
+
+water-to-light map:
+88 18 7",
+        );
+        assert!(broken.is_err());
+
+        let contradictory = Almanac::from_str(
+            "seeds: 1
+
+seed-to-soil map:
+50 98 2
+10 90 10",
+        );
+        assert!(contradictory.is_err());
+
+        let overlapping = Almanac::from_str(
+            "seeds: 1
+
+seed-to-soil map:
+50 98 2
+42 90 9",
+        )?;
+        Ok(assert_eq!(
+            vec![(
+                "seed-to-soil".to_string(),
+                Overlap {
+                    first: 0,
+                    second: 1,
+                    contradictory: false
+                }
+            )],
+            overlapping.overlaps()
+        ))
+    }
+
+    #[test]
+    fn split_interval() -> Result<()> {
+        let map = SeedMap::from_str("seed-to-soil map:\n50 98 2\n52 50 48")?;
+        Ok(assert_eq!(
+            vec![(98..100, -48), (50..98, 2), (40..50, 0), (100..110, 0)],
+            map.split(40..110)
+        ))
     }
 }
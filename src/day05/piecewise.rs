@@ -0,0 +1,80 @@
+use anyhow::{Context, Result};
+use std::cmp::{max, min};
+
+use super::Interval;
+use crate::utils::AocError::*;
+
+/// All values the almanac can work with. Large enough for any input while
+/// leaving room to add offsets without overflowing.
+pub const DOMAIN: Interval = i64::MIN as i128..i64::MAX as i128;
+
+/// A function that shifts every interval of its domain by a constant
+/// offset. The pieces are sorted and disjoint.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PiecewiseMap {
+    pieces: Vec<(Interval, i128)>,
+}
+
+impl PiecewiseMap {
+    /// Build the map from source intervals and their offsets. Neighbouring
+    /// pieces with the same offset are merged.
+    pub fn new(mut pieces: Vec<(Interval, i128)>) -> Self {
+        pieces.retain(|(p, _)| !p.is_empty());
+        pieces.sort_by_key(|(p, _)| p.start);
+
+        let mut merged: Vec<(Interval, i128)> = vec![];
+        for (piece, offset) in pieces {
+            match merged.last_mut() {
+                Some((last, o)) if last.end == piece.start && *o == offset => last.end = piece.end,
+                _ => merged.push((piece, offset)),
+            }
+        }
+
+        PiecewiseMap { pieces: merged }
+    }
+
+    pub fn pieces(&self) -> &[(Interval, i128)] {
+        &self.pieces
+    }
+
+    /// The value for `x` or `None` if `x` is not in the domain of the map.
+    pub fn map(&self, x: i128) -> Option<i128> {
+        let i = self.pieces.partition_point(|(p, _)| p.end <= x);
+        self.pieces
+            .get(i)
+            .filter(|(p, _)| p.contains(&x))
+            .map(|(_, o)| x + o)
+    }
+
+    /// All values that are mapped into the interval.
+    pub fn preimage(&self, interval: &Interval) -> Vec<Interval> {
+        let pieces = self.pieces.iter().filter_map(|(p, o)| {
+            let start = max(p.start + o, interval.start);
+            let end = min(p.end + o, interval.end);
+            (start < end).then_some(start - o..end - o)
+        });
+        Self::new(pieces.map(|p| (p, 0)).collect())
+            .pieces
+            .into_iter()
+            .map(|(p, _)| p)
+            .collect()
+    }
+
+    /// The map in the other direction. Fails if two values are mapped to the
+    /// same one.
+    pub fn inverse(&self) -> Result<PiecewiseMap> {
+        let inverse = PiecewiseMap::new(
+            self.pieces
+                .iter()
+                .map(|(p, o)| (p.start + o..p.end + o, -o))
+                .collect(),
+        );
+
+        let overlapping = inverse.pieces.windows(2).any(|w| w[1].0.start < w[0].0.end);
+        if overlapping {
+            return Err(GenericError).context("Map can not be inverted, it is not injective");
+        }
+
+        Ok(inverse)
+    }
+}
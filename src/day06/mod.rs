@@ -1,21 +1,33 @@
 use anyhow::{Context, Result};
+use num::{integer::Roots, BigUint, CheckedMul, CheckedSub, Integer};
+use std::{error::Error, fmt::Display, str::FromStr};
 
-use crate::utils::AocError::*;
+use crate::{explain::Explanation, utils::AocError::*};
 
-#[derive(Debug)]
-pub struct Race {
-    time: u128,
-    distance: u128,
+/// A race with times and distances of any integer type, e.g. `u128` or
+/// `BigUint` for races that do not fit into a machine word.
+#[derive(Debug, Clone)]
+pub struct Race<T = u128> {
+    time: T,
+    distance: T,
 }
 
-fn parse_line(s: &str) -> Result<Vec<u128>> {
+fn parse_line<T>(s: &str) -> Result<Vec<T>>
+where
+    T: FromStr,
+    T::Err: Error + Send + Sync + 'static,
+{
     let mut split = s.split(':');
     let nums_str = split.nth(1).ok_or(GenericError).context("Could not parse nums")?;
-    nums_str.trim().split(' ').filter(|v| !v.is_empty()).map(|v| Ok(v.trim().parse::<u128>()?)).collect::<Result<Vec<_>>>()
+    nums_str.trim().split(' ').filter(|v| !v.is_empty()).map(|v| Ok(v.trim().parse::<T>()?)).collect::<Result<Vec<_>>>()
 }
 
-#[aoc_generator(day06)]
-pub fn input_generator(input: &str) -> Result<Vec<Race>> {
+/// Parse the races into any integer type.
+pub fn parse_races<T>(input: &str) -> Result<Vec<Race<T>>>
+where
+    T: FromStr,
+    T::Err: Error + Send + Sync + 'static,
+{
     let mut lines = input
         .lines()
         .filter(|s| !s.is_empty());
@@ -37,40 +49,160 @@ pub fn input_generator(input: &str) -> Result<Vec<Race>> {
     Ok(races)
 }
 
-fn how_to_win_race(r: &Race) -> u128 {
-    let mut win = 0;
+#[aoc_generator(day06)]
+pub fn input_generator(input: &str) -> Result<Vec<Race>> {
+    parse_races(input)
+}
+
+impl<T: Integer + Roots + Clone + CheckedMul + CheckedSub> Race<T> {
+    /// Whether `hold * (time - hold) > distance`, compared as
+    /// `time - hold > distance / hold` so the product can not overflow.
+    fn wins(&self, hold: &T) -> bool {
+        *hold > T::zero()
+            && *hold <= self.time
+            && self.time.clone() - hold.clone() > self.distance.clone() / hold.clone()
+    }
+
+    /// The shortest and the longest time to hold the button that beat the
+    /// record, both inclusive, or `None` if the record can not be beaten.
+    ///
+    /// Solves `h * (T - h) > D` exactly. The roots of the quadratic are
+    /// `(T ± sqrt(T² - 4D)) / 2`, the integer square root gets the lower
+    /// bound within one step and the upper bound follows from symmetry.
+    /// If `T²` does not fit into the integer type, the lower bound is found
+    /// by bisection instead.
+    pub fn winning_holds(&self) -> Option<(T, T)> {
+        let two = T::one() + T::one();
+        let half = self.time.clone() / two;
+
+        // No hold travels further than the one closest to T / 2
+        if !self.wins(&half) {
+            return None;
+        }
 
-    for i in 0..=r.time {
-        let speed = i;
-        let distance = (r.time - i) * speed;
+        let mut lo = self
+            .estimate_shortest()
+            .unwrap_or_else(|| self.bisect_shortest(half.clone()));
 
-        if distance > r.distance {
-            win += 1;
+        while lo > T::zero() && self.wins(&(lo.clone() - T::one())) {
+            lo = lo - T::one();
+        }
+        while lo <= half && !self.wins(&lo) {
+            lo = lo + T::one();
         }
+
+        let hi = self.time.clone() - lo.clone();
+        (lo <= hi).then_some((lo, hi))
+    }
+
+    /// `(T - sqrt(T² - 4D)) / 2`, or `None` if an intermediate overflows.
+    fn estimate_shortest(&self) -> Option<T> {
+        let two = T::one() + T::one();
+        let square = self.time.checked_mul(&self.time)?;
+        let four_distance = (two.clone() * two.clone()).checked_mul(&self.distance)?;
+        let discriminant = square.checked_sub(&four_distance)?;
+        Some((self.time.clone() - discriminant.sqrt()) / two)
+    }
+
+    /// The shortest winning hold in `[0, half]`, given that `half` wins.
+    fn bisect_shortest(&self, half: T) -> T {
+        let two = T::one() + T::one();
+        let (mut lo, mut hi) = (T::zero(), half);
+        while lo < hi {
+            let mid = lo.clone() + (hi.clone() - lo.clone()) / two.clone();
+            if self.wins(&mid) {
+                hi = mid;
+            } else {
+                lo = mid + T::one();
+            }
+        }
+        lo
+    }
+
+    /// The number of hold times that beat the record.
+    pub fn ways_to_win(&self) -> T {
+        self.winning_holds()
+            .map_or(T::zero(), |(lo, hi)| hi - lo + T::one())
     }
-    
-    win
+}
+
+fn product_of_ways(races: &[Race]) -> Result<u128> {
+    races
+        .iter()
+        .try_fold(1_u128, |acc, r| acc.checked_mul(r.ways_to_win()))
+        .ok_or(GenericError)
+        .context("The product of the ways to win does not fit into a u128")
 }
 
 #[aoc(day06, part1)]
 pub fn solve_part1(input: &[Race]) -> Result<u128> {
-    let result = input.iter().map(how_to_win_race).product::<u128>();
-    Ok(result)
+    product_of_ways(input)
 }
 
-pub fn concat_numbers<I>(iter: I) -> Result<u128> where I: Iterator<Item = u128> {
-    Ok(iter.fold("".to_string(), |acc, val| format!("{}{}", acc, val)).parse::<u128>()?)
+pub fn concat_numbers<T, I>(iter: I) -> Result<T>
+where
+    I: Iterator<Item = T>,
+    T: Display + FromStr,
+    T::Err: Error + Send + Sync + 'static,
+{
+    Ok(iter.fold("".to_string(), |acc, val| format!("{}{}", acc, val)).parse::<T>()?)
+
+}
 
+/// The single race of part 2 with all times and all distances concatenated.
+pub fn concat_races<T>(input: &[Race<T>]) -> Result<Race<T>>
+where
+    T: Display + FromStr + Clone,
+    T::Err: Error + Send + Sync + 'static,
+{
+    let time = concat_numbers(input.iter().map(|r| r.time.clone()))?;
+    let distance = concat_numbers(input.iter().map(|r| r.distance.clone()))?;
+    Ok(Race { time, distance })
 }
 
 #[aoc(day06, part2)]
 pub fn solve_part2(input: &[Race]) -> Result<u128> {
-    let time = concat_numbers(input.iter().map(|r| r.time))?;
-    let distance = concat_numbers(input.iter().map(|r| r.distance))?;
-    let input = vec![Race { time, distance }];
+    let race = concat_races(input)
+        .context("The concatenated race does not fit into a u128, use solve_part2_big")?;
+    Ok(race.ways_to_win())
+}
+
+fn explain(races: &[Race]) -> Result<Explanation> {
+    let rows = races
+        .iter()
+        .map(|r| {
+            let (lo, hi) = match r.winning_holds() {
+                Some((lo, hi)) => (lo.to_string(), hi.to_string()),
+                None => ("-".to_string(), "-".to_string()),
+            };
+            vec![
+                r.time.to_string(),
+                r.distance.to_string(),
+                lo,
+                hi,
+                r.ways_to_win().to_string(),
+            ]
+        })
+        .collect();
+
+    Ok(Explanation::new(
+        &["time", "record", "shortest hold", "longest hold", "ways"],
+        rows,
+        product_of_ways(races)?,
+    ))
+}
+
+pub fn explain_part1(input: &[Race]) -> Result<Explanation> {
+    explain(input)
+}
 
-    let result = input.iter().map(how_to_win_race).product::<u128>();
-    Ok(result)
+pub fn explain_part2(input: &[Race]) -> Result<Explanation> {
+    explain(&[concat_races(input)?])
+}
+
+/// Part 2 for races whose concatenation does not fit into a `u128`.
+pub fn solve_part2_big(input: &str) -> Result<BigUint> {
+    Ok(concat_races(&parse_races::<BigUint>(input)?)?.ways_to_win())
 }
 
 #[cfg(test)]
@@ -97,4 +229,81 @@ Distance:  9  40  200"
         let data = input()?;
         Ok(assert_eq!(71503, solve_part2(&data)?))
     }
+
+    #[test]
+    fn part1_explain() -> Result<()> {
+        let data = input()?;
+        let explanation = explain_part1(&data)?;
+        assert_eq!("288", explanation.answer);
+        Ok(assert_eq!(
+            vec!["30", "200", "11", "19", "9"],
+            explanation.rows[2]
+        ))
+    }
+
+    #[test]
+    fn winning_holds() -> Result<()> {
+        let data = input()?;
+        assert_eq!(Some((2, 5)), data[0].winning_holds());
+        assert_eq!(Some((4, 11)), data[1].winning_holds());
+        assert_eq!(Some((11, 19)), data[2].winning_holds());
+        assert_eq!(11, data[2].bisect_shortest(15));
+
+        let record = Race {
+            time: 4,
+            distance: 4,
+        };
+        Ok(assert_eq!(None, record.winning_holds()))
+    }
+
+    #[test]
+    fn huge_races() -> Result<()> {
+        // T² does not fit into a u128 anymore
+        let data = input_generator("Time: 10000000000000000000000000\nDistance: 5")?;
+        assert_eq!(9_999_999_999_999_999_999_999_999, solve_part1(&data)?);
+
+        let race = Race {
+            time: u128::MAX,
+            distance: u128::MAX / 4,
+        };
+        let (lo, hi) = race.winning_holds().ok_or(GenericError)?;
+        assert!(race.wins(&lo) && !race.wins(&(lo - 1)));
+        assert!(race.wins(&hi) && !race.wins(&(hi + 1)));
+
+        // The product of the ways of two such races overflows
+        let twice = input_generator(&format!("Time: {0} {0}\nDistance: 5 5", u128::MAX))?;
+        Ok(assert!(solve_part1(&twice).is_err()))
+    }
+
+    #[test]
+    fn long_concatenation() -> Result<()> {
+        // A 25 digit time fits into a u128, but its square does not
+        let long = format!(
+            "Time: {}\nDistance: {}",
+            "71530 ".repeat(5),
+            "940200 ".repeat(5)
+        );
+        let ways = solve_part2(&input_generator(&long)?)?;
+        assert_eq!(7_153_071_530_715_307_152_808_649, ways);
+        Ok(assert_eq!(BigUint::from(ways), solve_part2_big(&long)?))
+    }
+
+    #[test]
+    fn big_races() -> Result<()> {
+        assert_eq!(BigUint::from(71503_u32), solve_part2_big(sample())?);
+
+        // The concatenated race is far beyond u128
+        let long = format!(
+            "Time: {}\nDistance: {}",
+            "71530 ".repeat(8),
+            "940200 ".repeat(8)
+        );
+        assert!(solve_part2(&input_generator(&long)?).is_err());
+
+        let race = concat_races(&parse_races::<BigUint>(&long)?)?;
+        let (lo, hi) = race.winning_holds().ok_or(GenericError)?;
+        assert!(race.wins(&lo) && race.wins(&hi));
+        assert!(!race.wins(&(lo.clone() - 1_u32)) && !race.wins(&(hi.clone() + 1_u32)));
+        Ok(assert_eq!(hi - lo + 1_u32, race.ways_to_win()))
+    }
 }
@@ -1,9 +1,15 @@
-use std::{str::FromStr, cmp::Ordering};
+use std::{str::FromStr, cmp::Ordering, fmt::{self, Display}};
 
 use anyhow::{Error, Result, Context};
 
 use crate::utils::AocError::*;
 
+use super::rules::Rules;
+
+lazy_static! {
+    static ref DEFAULT_RULES: Rules = Rules::default();
+}
+
 #[derive(Debug, Clone, Eq, PartialEq, PartialOrd, Ord)]
 pub enum Card {
     Joker,
@@ -23,7 +29,7 @@ pub enum Card {
 }
 
 impl Card {
-    fn from_char(s: char) -> Result<Self> {
+    pub fn from_char(s: char) -> Result<Self> {
         use Card::*;
 
         Ok(match s {
@@ -44,9 +50,31 @@ impl Card {
             _ => Err(GenericError).context("Could not parse card")?
         })
     }
+
+    pub fn to_char(&self) -> char {
+        use Card::*;
+
+        match self {
+            Ace => 'A',
+            King => 'K',
+            Queen => 'Q',
+            Jack => 'J',
+            Ten => 'T',
+            Nine => '9',
+            Eight => '8',
+            Seven => '7',
+            Six => '6',
+            Five => '5',
+            Four => '4',
+            Three => '3',
+            Two => '2',
+            // jokers are written as J in the puzzle input
+            Joker => 'J',
+        }
+    }
 }
 
-#[derive(PartialEq, PartialOrd, Debug, Eq, Ord)]
+#[derive(PartialEq, PartialOrd, Debug, Clone, Eq, Ord)]
 pub enum Kind {
     HighCard,
     OnePair,
@@ -57,6 +85,25 @@ pub enum Kind {
     Five,
 }
 
+impl Kind {
+    /// The kind of a hand from the sizes of its groups of equal cards, the
+    /// largest first. Larger hands are named after their two largest groups.
+    pub fn from_shape(shape: &[usize]) -> Self {
+        match (
+            shape.first().copied().unwrap_or(0),
+            shape.get(1).copied().unwrap_or(0),
+        ) {
+            (5.., _) => Kind::Five,
+            (4, _) => Kind::Four,
+            (3, 2..) => Kind::FullHouse,
+            (3, _) => Kind::Three,
+            (2, 2..) => Kind::TwoPair,
+            (2, _) => Kind::OnePair,
+            _ => Kind::HighCard,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct Hand {
     pub cards: Vec<Card>,
@@ -77,10 +124,6 @@ impl FromStr for Hand {
             .map(Card::from_char)
             .collect::<Result<Vec<_>>>()?;
 
-        if cards.len() != 5 {
-            return Err(GenericError).context("Not exactly five cards");
-        }
-
         let bid = split
             .next()
             .ok_or(GenericError)
@@ -92,22 +135,6 @@ impl FromStr for Hand {
 }
 
 impl Hand {
-    pub fn partition(hand: &[Card]) -> Vec<u8> {
-        let mut result = vec![1];
-        let mut pos = 0;
-
-        for i in 0..4 {
-            if hand[i] == hand[i + 1] {
-                result[pos] += 1;
-            } else {
-                result.push(1);
-                pos += 1;
-            }
-        }
-
-        result
-    }
-
     pub fn to_joker(&self) -> Self {
         use Card::*;
         let cards = self.cards.iter().map(|c| if *c == Jack { Joker } else { c.clone() }).collect::<Vec<_>>();
@@ -115,83 +142,23 @@ impl Hand {
         Hand { cards, bid }
     }
 
+    /// The kind with the default rules, where only jokers are wild.
     pub fn kind(&self) -> Kind {
-        use Card::*;
-        let mut scores = self.cards.clone();
-        scores.sort();
-
-        let mut partition = Self::partition(&scores);
-        partition.sort();
-
-        if partition.len() == 1 && partition[0] == 5 {
-            return Kind::Five;
-        }
-
-        if partition.len() == 2 && partition[0] == 1 && partition[1] == 4 {
-            if scores[0] == Joker {
-                return Kind::Five;
-            }
-
-            return Kind::Four;
-        }
-
-        if partition.len() == 2 && partition[0] == 2 && partition[1] == 3 {
-            if scores[0] == Joker {
-                return Kind::Five;
-            }
-            return Kind::FullHouse;
-        }
-
-        if partition.len() == 3 && partition[0] == 1 && partition[1] == 1 && partition[2] == 3 {
-            if scores[0] == Joker {
-                return Kind::Four;
-            }
-            return Kind::Three;
-        }
-
-        if partition.len() == 3 && partition[0] == 1 && partition[1] == 2 && partition[2] == 2 {
-            if scores[1] == Joker {
-                return Kind::Four;
-            }
-
-            if scores[0] == Joker {
-                return Kind::FullHouse;
-            }
-            return Kind::TwoPair;
-        }
-
-        if partition.len() == 4 {
-            if scores[0] == Joker {
-                return Kind::Three;
-            }
-            return Kind::OnePair;
-        }
-
-        if scores[0] == Joker {
-            return Kind::OnePair;
-        }
+        DEFAULT_RULES.kind(self)
+    }
+}
 
-        Kind::HighCard
+impl Display for Hand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.cards.iter().map(|c| c.to_char()).collect::<String>())
     }
 }
 
 impl Ord for Hand {
     fn cmp(&self, other: &Self) -> Ordering {
-            let self_kind = self.kind();
-            let other_kind = other.kind();
-            let kind_match = self_kind == other_kind;
-
-            if !kind_match {
-                self_kind.cmp(&other_kind)
-            } else {
-                for i in 0..5 {
-                    if self.cards[i] != other.cards[i] {
-                        return self.cards[i].cmp(&other.cards[i]);
-                    }
-                }
-
-                Ordering::Equal
-            }
+        DEFAULT_RULES
+            .cmp(self, other)
+            .expect("The default rules rank every card")
     }
 }
 
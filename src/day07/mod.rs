@@ -1,10 +1,15 @@
 mod hand;
+pub mod poker;
+mod rules;
 
-use std::str::FromStr;
+use std::{io::BufRead, str::FromStr};
 
 use anyhow::{Result, Context};
 
-use hand::Hand;
+use crate::{explain::Explanation, utils::read_lines};
+
+pub use hand::{Card, Hand, Kind};
+pub use rules::{RankedHand, Rules, TieBreak};
 
 #[aoc_generator(day07)]
 pub fn input_generator(input: &str) -> Result<Vec<Hand>> {
@@ -16,37 +21,58 @@ pub fn input_generator(input: &str) -> Result<Vec<Hand>> {
         .context("Error while parsing input")
 }
 
+pub fn input_reader<R: BufRead>(reader: R) -> impl Iterator<Item = Result<Hand>> {
+    read_lines(reader, Hand::from_str)
+}
+
 #[aoc(day07, part1)]
 pub fn solve_part1(input: &[Hand]) -> Result<usize> {
-    let mut hands = input.to_vec();
-    hands.sort();
-
-    let result = hands
-        .iter()
-        .enumerate()
-        .map(|(i, h)| (i + 1) * (h.bid as usize))
-        .sum::<usize>();
-
-    Ok(result)
+    Rules::part1().winnings(input)
 }
 
 #[aoc(day07, part2)]
 pub fn solve_part2(input: &[Hand]) -> Result<usize> {
-    let mut hands = input.iter().map(|h| h.to_joker()).collect::<Vec<_>>();
-    hands.sort();
+    Rules::part2().winnings(input)
+}
+
+fn explain(input: &[Hand], rules: &Rules) -> Result<Explanation> {
+    let ranked = rules.rank(input)?;
 
-    let result = hands
+    let rows = ranked
         .iter()
-        .enumerate()
-        .map(|(i, h)| (i + 1) * (h.bid as usize))
-        .sum::<usize>();
+        .map(|r| {
+            vec![
+                r.rank.to_string(),
+                r.hand.to_string(),
+                format!("{:?}", r.kind),
+                r.best_joker_substitution
+                    .as_ref()
+                    .map_or("-".to_string(), |c| c.to_char().to_string()),
+                r.hand.bid.to_string(),
+                r.winnings.to_string(),
+            ]
+        })
+        .collect();
 
-    Ok(result)
+    Ok(Explanation::new(
+        &["rank", "hand", "kind", "joker", "bid", "winnings"],
+        rows,
+        ranked.iter().map(|r| r.winnings).sum::<usize>(),
+    ))
+}
+
+pub fn explain_part1(input: &[Hand]) -> Result<Explanation> {
+    explain(input, &Rules::part1())
+}
+
+pub fn explain_part2(input: &[Hand]) -> Result<Explanation> {
+    explain(input, &Rules::part2())
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
+    use std::cmp::Ordering;
 
     fn sample() -> &'static str {
         "32T3K 765
@@ -71,4 +97,118 @@ QQQJA 483"
         let data = input()?;
         Ok(assert_eq!(5905, solve_part2(&data)?))
     }
+
+    #[test]
+    fn part2_explain() -> Result<()> {
+        let data = input()?;
+        let explanation = explain_part2(&data)?;
+        assert_eq!("5905", explanation.answer);
+        Ok(assert_eq!(
+            vec!["5", "KTJJT", "Four", "T", "220", "1100"],
+            explanation.rows[4]
+        ))
+    }
+
+    #[test]
+    fn jokers_match_wild_jacks() -> Result<()> {
+        let data = input()?;
+        let jokers = data.iter().map(|h| h.to_joker()).collect::<Vec<_>>();
+        Ok(assert_eq!(
+            solve_part2(&data)?,
+            Rules::default().winnings(&jokers)?
+        ))
+    }
+
+    #[test]
+    fn ranked_hands() -> Result<()> {
+        let data = input()?;
+        let ranked = Rules::part2().rank(&data)?;
+        assert_eq!(
+            vec!["32T3K", "KK677", "T55J5", "QQQJA", "KTJJT"],
+            ranked
+                .iter()
+                .map(|r| r.hand.to_string())
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(None, ranked[1].best_joker_substitution);
+        assert_eq!(Some(Card::Five), ranked[2].best_joker_substitution);
+        assert_eq!(Kind::Four, ranked[2].kind);
+        assert_eq!(5 * 220, ranked[4].winnings);
+
+        let all_jokers = Rules::part2().rank(&[Hand::from_str("JJJJJ 1")?])?;
+        Ok(assert_eq!(
+            Some(Card::Ace),
+            all_jokers[0].best_joker_substitution
+        ))
+    }
+
+    #[test]
+    fn poker_hands() -> Result<()> {
+        use poker::{evaluate, parse_cards, Category};
+
+        let eval = |s| evaluate(&parse_cards(s)?);
+        let straight_flush = eval("9h Th Jh Qh Kh")?;
+        let four = eval("9h 9c 9d 9s Kh")?;
+        let wheel = eval("Ah 2c 3d 4s 5h")?;
+        let six_high = eval("2c 3d 4s 5h 6h")?;
+        assert_eq!(Category::StraightFlush, straight_flush.category);
+        assert!(straight_flush > four);
+        assert_eq!(Category::Straight, wheel.category);
+        assert!(six_high > wheel);
+        assert!(eval("2h 7h 9h Jh Kh")? > six_high);
+        assert!(eval("Kh Kc 9d 9s 2h")? > eval("Qh Qc Jd Js Ah")?);
+
+        // The best five of seven cards
+        let seven = eval("Ah Kd 2h 3c 4h 5h 9h")?;
+        assert_eq!(Category::Flush, seven.category);
+        assert!(parse_cards("Xh").is_err());
+
+        // No deck has five cards of a rank or the same card twice
+        assert!(eval("Ah Ah Ac Ad As").is_err());
+        assert!(eval("Kh Kh Kc Kd Ks").is_err());
+        assert!(eval("Kh Kh Qc Jd 2s").is_err());
+
+        assert!(Category::Four > Kind::FullHouse);
+        Ok(assert!(Category::Straight < Kind::FullHouse))
+    }
+
+    #[test]
+    fn poker_equity() -> Result<()> {
+        use poker::{equity, parse_cards};
+
+        let players = [parse_cards("Ah Ad")?, parse_cards("Kh Kd")?];
+        let equity = equity(&players, &parse_cards("2c 7s 9h Qd")?)?;
+        assert!((equity[0] - 42.0 / 44.0).abs() < 1e-9);
+        assert!((equity[1] - 2.0 / 44.0).abs() < 1e-9);
+        Ok(assert!(
+            poker::equity(&players, &parse_cards("Ah 7s 9h")?).is_err()
+        ))
+    }
+
+    #[test]
+    fn custom_rules() -> Result<()> {
+        let rules = Rules::new(7, "23456789TJQKA", "2", TieBreak::HighCards)?;
+        let hand = Hand::from_str("AAK2KQ3 10")?;
+        assert_eq!(vec![3, 2, 1, 1], rules.shape(&hand));
+        assert_eq!(Kind::FullHouse, rules.kind(&hand));
+        assert!(rules.validate(&Hand::from_str("AAKKQ 1")?).is_err());
+
+        // The high cards decide, not the order of the cards
+        let a = Hand::from_str("2345679 1")?;
+        let b = Hand::from_str("9765432 1")?;
+        assert_eq!(Ordering::Equal, rules.cmp(&a, &b)?);
+
+        let wild = Rules::new(3, "AKQ", "AKQ", TieBreak::FirstCard)?;
+        assert_eq!(Kind::Three, wild.kind(&Hand::from_str("AKQ 1")?));
+        // Every card has to be ranked to be compared
+        let faces = Rules::new(5, "JQKA", "J", TieBreak::FirstCard)?;
+        assert!(faces.strength(&Card::Two).is_err());
+        assert!(faces
+            .cmp(&Hand::from_str("JQKA2 1")?, &Hand::from_str("JQKAA 1")?)
+            .is_err());
+        assert!(Rules::new(5, "QKA", "J", TieBreak::FirstCard).is_err());
+        Ok(assert!(
+            Rules::new(5, "AKA", "", TieBreak::FirstCard).is_err()
+        ))
+    }
 }
@@ -0,0 +1,266 @@
+use std::{cmp::Ordering, fmt, str::FromStr};
+
+use anyhow::{Context, Error, Result};
+use itertools::Itertools;
+
+use super::hand::{Card, Kind};
+use crate::utils::AocError::*;
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, PartialOrd, Ord)]
+pub enum Suit {
+    Clubs,
+    Diamonds,
+    Hearts,
+    Spades,
+}
+
+const SUITS: [Suit; 4] = [Suit::Clubs, Suit::Diamonds, Suit::Hearts, Suit::Spades];
+
+impl Suit {
+    fn from_char(c: char) -> Result<Self> {
+        use Suit::*;
+
+        Ok(match c {
+            'c' => Clubs,
+            'd' => Diamonds,
+            'h' => Hearts,
+            's' => Spades,
+            _ => Err(GenericError).context("Could not parse suit")?,
+        })
+    }
+
+    fn to_char(self) -> char {
+        use Suit::*;
+
+        match self {
+            Clubs => 'c',
+            Diamonds => 'd',
+            Hearts => 'h',
+            Spades => 's',
+        }
+    }
+}
+
+/// A card of a standard deck, written as rank and suit, e.g. 'Ah' or 'Tc'.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct PokerCard {
+    pub rank: Card,
+    pub suit: Suit,
+}
+
+impl FromStr for PokerCard {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let mut chars = s.chars();
+        let rank = chars
+            .next()
+            .ok_or(GenericError)
+            .context("Could not parse rank")
+            .and_then(Card::from_char)?;
+        let suit = chars
+            .next()
+            .ok_or(GenericError)
+            .context("Could not parse suit")
+            .and_then(Suit::from_char)?;
+
+        if rank == Card::Joker || chars.next().is_some() {
+            return Err(GenericError).with_context(|| format!("Not a poker card: {}", s));
+        }
+
+        Ok(PokerCard { rank, suit })
+    }
+}
+
+impl fmt::Display for PokerCard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", self.rank.to_char(), self.suit.to_char())
+    }
+}
+
+/// Parse cards separated by whitespace, e.g. 'Ah Kh 7c'.
+pub fn parse_cards(s: &str) -> Result<Vec<PokerCard>> {
+    s.split_whitespace().map(PokerCard::from_str).collect()
+}
+
+/// All 52 cards.
+pub fn deck() -> Vec<PokerCard> {
+    use Card::*;
+
+    [
+        Two, Three, Four, Five, Six, Seven, Eight, Nine, Ten, Jack, Queen, King, Ace,
+    ]
+    .into_iter()
+    .cartesian_product(SUITS)
+    .map(|(rank, suit)| PokerCard { rank, suit })
+    .collect()
+}
+
+/// The poker hand categories from the weakest to the strongest. Five of a
+/// kind only exists with wild cards but keeps the order comparable with the
+/// Camel Cards kinds.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, PartialOrd, Ord)]
+pub enum Category {
+    HighCard,
+    OnePair,
+    TwoPair,
+    Three,
+    Straight,
+    Flush,
+    FullHouse,
+    Four,
+    StraightFlush,
+    Five,
+}
+
+impl From<&Kind> for Category {
+    fn from(kind: &Kind) -> Self {
+        match kind {
+            Kind::HighCard => Category::HighCard,
+            Kind::OnePair => Category::OnePair,
+            Kind::TwoPair => Category::TwoPair,
+            Kind::Three => Category::Three,
+            Kind::FullHouse => Category::FullHouse,
+            Kind::Four => Category::Four,
+            Kind::Five => Category::Five,
+        }
+    }
+}
+
+impl PartialEq<Kind> for Category {
+    fn eq(&self, kind: &Kind) -> bool {
+        *self == Category::from(kind)
+    }
+}
+
+impl PartialOrd<Kind> for Category {
+    fn partial_cmp(&self, kind: &Kind) -> Option<Ordering> {
+        Some(self.cmp(&Category::from(kind)))
+    }
+}
+
+/// The value of a five card poker hand. Evaluations compare like the hands
+/// they were made from.
+#[derive(Debug, Clone, Eq, PartialEq, PartialOrd, Ord)]
+pub struct Evaluation {
+    pub category: Category,
+    /// The ranks that decide between hands of the same category, the most
+    /// important first.
+    pub ranks: Vec<Card>,
+}
+
+fn evaluate_five(cards: &[PokerCard]) -> Evaluation {
+    // Groups of equal ranks, the largest and then the highest first
+    let mut groups = cards
+        .iter()
+        .map(|c| c.rank.clone())
+        .sorted()
+        .dedup_with_count()
+        .collect::<Vec<_>>();
+    groups.sort_by(|a, b| b.cmp(a));
+    let ranks = groups.iter().map(|(_, r)| r.clone()).collect::<Vec<_>>();
+
+    let flush = cards.iter().all(|c| c.suit == cards[0].suit);
+    let straight = groups.len() == 5
+        && (ranks[0].clone() as usize - ranks[4].clone() as usize == 4
+            || ranks == [Card::Ace, Card::Five, Card::Four, Card::Three, Card::Two]);
+    // The ace counts as one in the lowest straight
+    let straight_ranks = if straight && ranks[0] == Card::Ace && ranks[1] == Card::Five {
+        ranks[1..].to_vec()
+    } else {
+        ranks.clone()
+    };
+
+    let category = match (groups[0].0, groups.get(1).map_or(0, |g| g.0)) {
+        _ if straight && flush => Category::StraightFlush,
+        (4, _) => Category::Four,
+        (3, 2) => Category::FullHouse,
+        _ if flush => Category::Flush,
+        _ if straight => Category::Straight,
+        (3, _) => Category::Three,
+        (2, 2) => Category::TwoPair,
+        (2, _) => Category::OnePair,
+        _ => Category::HighCard,
+    };
+
+    Evaluation {
+        category,
+        ranks: if straight { straight_ranks } else { ranks },
+    }
+}
+
+/// The best five card hand that can be made from the cards.
+pub fn evaluate(cards: &[PokerCard]) -> Result<Evaluation> {
+    if cards.len() < 5 {
+        return Err(GenericError).context("A poker hand needs at least five cards");
+    }
+    if let Some((count, rank)) = cards
+        .iter()
+        .map(|c| c.rank.clone())
+        .sorted()
+        .dedup_with_count()
+        .find(|(count, _)| *count > 4)
+    {
+        return Err(GenericError)
+            .with_context(|| format!("There are {} cards of rank {}", count, rank.to_char()));
+    }
+    if let Some(card) = cards
+        .iter()
+        .enumerate()
+        .find_map(|(i, c)| cards[i + 1..].contains(c).then_some(c))
+    {
+        return Err(GenericError).with_context(|| format!("The card {} is dealt twice", card));
+    }
+
+    cards
+        .iter()
+        .cloned()
+        .combinations(5)
+        .map(|hand| evaluate_five(&hand))
+        .max()
+        .ok_or(GenericError)
+        .context("Could not evaluate hand")
+}
+
+/// The share of the pot every player can expect, given their hole cards
+/// and the known cards of the board. All ways to complete the board to
+/// five cards are enumerated, ties split the pot.
+pub fn equity(players: &[Vec<PokerCard>], board: &[PokerCard]) -> Result<Vec<f64>> {
+    let known = players.iter().flatten().chain(board).collect::<Vec<_>>();
+    if known
+        .iter()
+        .enumerate()
+        .any(|(i, c)| known[i + 1..].contains(c))
+    {
+        return Err(GenericError).context("A card is dealt twice");
+    }
+    if board.len() > 5 {
+        return Err(GenericError).context("The board has more than five cards");
+    }
+
+    let remaining = deck()
+        .into_iter()
+        .filter(|c| !known.contains(&c))
+        .collect::<Vec<_>>();
+
+    let mut shares = vec![0.0; players.len()];
+    let mut boards = 0;
+    for rest in remaining.into_iter().combinations(5 - board.len()) {
+        let full_board = board.iter().cloned().chain(rest).collect::<Vec<_>>();
+        let evaluations = players
+            .iter()
+            .map(|p| evaluate(&[p.clone(), full_board.clone()].concat()))
+            .collect::<Result<Vec<_>>>()?;
+
+        let best = evaluations.iter().max().ok_or(GenericError)?;
+        let winners = evaluations.iter().filter(|e| *e == best).count();
+        for (share, evaluation) in shares.iter_mut().zip(&evaluations) {
+            if evaluation == best {
+                *share += 1.0 / winners as f64;
+            }
+        }
+        boards += 1;
+    }
+
+    Ok(shares.into_iter().map(|s| s / boards as f64).collect())
+}
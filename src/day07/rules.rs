@@ -0,0 +1,216 @@
+use std::{cmp::Ordering, collections::BTreeMap};
+
+use anyhow::{Context, Result};
+
+use super::hand::{Card, Hand, Kind};
+use crate::utils::AocError::*;
+
+/// How hands of the same shape are ordered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TieBreak {
+    /// Compare the cards in the order they were dealt, as in the puzzle.
+    FirstCard,
+    /// Compare the strongest cards first, as in poker.
+    HighCards,
+}
+
+/// A hand with its place on the table.
+#[derive(Debug, Clone)]
+pub struct RankedHand {
+    pub hand: Hand,
+    pub kind: Kind,
+    /// The card the wild cards of the hand stand for, if it has any.
+    pub best_joker_substitution: Option<Card>,
+    pub rank: usize,
+    pub winnings: usize,
+}
+
+/// The rules of a Camel Cards variant.
+#[derive(Debug, Clone)]
+pub struct Rules {
+    pub hand_size: usize,
+    /// All cards from the weakest to the strongest.
+    pub ranking: Vec<Card>,
+    /// Cards that act like whatever card makes the hand strongest.
+    pub wild: Vec<Card>,
+    pub tie_break: TieBreak,
+}
+
+impl Default for Rules {
+    /// Five cards where the joker is wild and the weakest card.
+    fn default() -> Self {
+        use Card::*;
+
+        Rules {
+            hand_size: 5,
+            ranking: vec![
+                Joker, Two, Three, Four, Five, Six, Seven, Eight, Nine, Ten, Jack, Queen, King, Ace,
+            ],
+            wild: vec![Joker],
+            tie_break: TieBreak::FirstCard,
+        }
+    }
+}
+
+impl Rules {
+    /// Cards are given by their letters, e.g. "23456789TJQKA".
+    pub fn new(hand_size: usize, ranking: &str, wild: &str, tie_break: TieBreak) -> Result<Self> {
+        let ranking = ranking
+            .chars()
+            .map(Card::from_char)
+            .collect::<Result<Vec<_>>>()?;
+        let wild = wild
+            .chars()
+            .map(Card::from_char)
+            .collect::<Result<Vec<_>>>()?;
+
+        if let Some(c) = ranking
+            .iter()
+            .enumerate()
+            .find(|(i, c)| ranking[i + 1..].contains(c))
+        {
+            return Err(GenericError).with_context(|| format!("Card {:?} is ranked twice", c.1));
+        }
+        if let Some(c) = wild.iter().find(|c| !ranking.contains(c)) {
+            return Err(GenericError).with_context(|| format!("Wild card {:?} is not ranked", c));
+        }
+
+        Ok(Rules {
+            hand_size,
+            ranking,
+            wild,
+            tie_break,
+        })
+    }
+
+    pub fn part1() -> Self {
+        Self::new(5, "23456789TJQKA", "", TieBreak::FirstCard).expect("Part 1 rules are invalid")
+    }
+
+    /// Jacks are wild and the weakest card.
+    pub fn part2() -> Self {
+        Self::new(5, "J23456789TQKA", "J", TieBreak::FirstCard).expect("Part 2 rules are invalid")
+    }
+
+    /// Check that the hand can be played with these rules.
+    pub fn validate(&self, hand: &Hand) -> Result<()> {
+        if hand.cards.len() != self.hand_size {
+            return Err(GenericError)
+                .with_context(|| format!("{} does not have {} cards", hand, self.hand_size));
+        }
+
+        match hand.cards.iter().find(|c| !self.ranking.contains(c)) {
+            Some(c) => Err(GenericError).with_context(|| format!("Card {:?} is not ranked", c)),
+            None => Ok(()),
+        }
+    }
+
+    /// The position of the card in the ranking, the weakest card is 0.
+    pub fn strength(&self, card: &Card) -> Result<usize> {
+        self.ranking
+            .iter()
+            .position(|c| c == card)
+            .ok_or(GenericError)
+            .with_context(|| format!("Card {:?} is not ranked", card))
+    }
+
+    /// How many cards of a kind the hand has, the largest group first. The
+    /// wild cards all join the largest group, which makes the strongest
+    /// shape possible.
+    pub fn shape(&self, hand: &Hand) -> Vec<usize> {
+        let mut counts = BTreeMap::new();
+        for card in hand.cards.iter().filter(|c| !self.wild.contains(c)) {
+            *counts.entry(card).or_insert(0) += 1;
+        }
+
+        let mut shape = counts.into_values().collect::<Vec<_>>();
+        shape.sort_by(|a, b| b.cmp(a));
+        if shape.is_empty() {
+            shape.push(0);
+        }
+        shape[0] += hand.cards.iter().filter(|c| self.wild.contains(c)).count();
+
+        shape
+    }
+
+    pub fn kind(&self, hand: &Hand) -> Kind {
+        Kind::from_shape(&self.shape(hand))
+    }
+
+    /// The card that all wild cards of the hand should become to make the
+    /// strongest shape, see `shape`. The strongest of the most frequent
+    /// cards is picked.
+    pub fn substitution(&self, hand: &Hand) -> Result<Option<Card>> {
+        if !hand.cards.iter().any(|c| self.wild.contains(c)) {
+            return Ok(None);
+        }
+
+        let mut counts = BTreeMap::new();
+        for card in hand.cards.iter().filter(|c| !self.wild.contains(c)) {
+            *counts.entry(card).or_insert(0) += 1;
+        }
+
+        Ok(counts
+            .into_iter()
+            .map(|(c, n)| Ok((n, self.strength(c)?, c)))
+            .collect::<Result<Vec<_>>>()?
+            .into_iter()
+            .max()
+            .map(|(_, _, c)| c)
+            .or(self.ranking.last())
+            .cloned())
+    }
+
+    fn tie_break_key(&self, hand: &Hand) -> Result<Vec<usize>> {
+        let mut key = hand
+            .cards
+            .iter()
+            .map(|c| self.strength(c))
+            .collect::<Result<Vec<_>>>()?;
+        if self.tie_break == TieBreak::HighCards {
+            key.sort_by(|a, b| b.cmp(a));
+        }
+        Ok(key)
+    }
+
+    /// Hands with a stronger shape win, otherwise the tie break decides.
+    pub fn cmp(&self, a: &Hand, b: &Hand) -> Result<Ordering> {
+        Ok(self
+            .shape(a)
+            .cmp(&self.shape(b))
+            .then(self.tie_break_key(a)?.cmp(&self.tie_break_key(b)?)))
+    }
+
+    /// Sort the hands from the weakest to the strongest. Every hand is only
+    /// classified once.
+    pub fn rank(&self, hands: &[Hand]) -> Result<Vec<RankedHand>> {
+        let mut keyed = hands
+            .iter()
+            .map(|h| {
+                self.validate(h)?;
+                Ok(((self.shape(h), self.tie_break_key(h)?), h))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        keyed.sort_by(|a, b| a.0.cmp(&b.0));
+
+        keyed
+            .into_iter()
+            .enumerate()
+            .map(|(i, ((shape, _), hand))| {
+                Ok(RankedHand {
+                    hand: hand.clone(),
+                    kind: Kind::from_shape(&shape),
+                    best_joker_substitution: self.substitution(hand)?,
+                    rank: i + 1,
+                    winnings: (i + 1) * (hand.bid as usize),
+                })
+            })
+            .collect()
+    }
+
+    /// The total winnings of all hands, each hand wins its bid times its
+    /// rank.
+    pub fn winnings(&self, hands: &[Hand]) -> Result<usize> {
+        Ok(self.rank(hands)?.iter().map(|r| r.winnings).sum())
+    }
+}
@@ -0,0 +1,112 @@
+use anyhow::{Context, Result};
+use num::Integer;
+use std::collections::BTreeSet;
+
+use crate::utils::AocError::*;
+
+const OVERFLOW: &str = "The combined cycle length does not fit into an i128";
+
+/// Where a walk through the (node, instruction) states reaches its goal.
+/// After `tail` steps the walk repeats itself every `length` steps.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cycle {
+    pub tail: usize,
+    pub length: usize,
+    /// Steps before the cycle starts at which a goal is reached.
+    pub tail_hits: Vec<usize>,
+    /// Steps within the first round of the cycle at which a goal is reached,
+    /// each of them repeats every `length` steps.
+    pub cycle_hits: Vec<usize>,
+}
+
+impl Cycle {
+    pub fn is_hit(&self, step: usize) -> bool {
+        if step < self.tail {
+            self.tail_hits.contains(&step)
+        } else {
+            let offset = self.tail + (step - self.tail) % self.length;
+            self.cycle_hits.contains(&offset)
+        }
+    }
+}
+
+/// Solve `x ≡ a (mod m)` for two congruences whose moduli do not need to be
+/// coprime. The result is the combined congruence or `None` if there is no
+/// solution. Fails if the combined modulus does not fit into an `i128`.
+pub fn crt((a1, m1): (i128, i128), (a2, m2): (i128, i128)) -> Result<Option<(i128, i128)>> {
+    let e = m1.extended_gcd(&m2);
+    if (a2 - a1) % e.gcd != 0 {
+        return Ok(None);
+    }
+
+    let lcm = (m1 / e.gcd)
+        .checked_mul(m2)
+        .ok_or(GenericError)
+        .context(OVERFLOW)?;
+    let k = ((a2 - a1) / e.gcd)
+        .mod_floor(&(m2 / e.gcd))
+        .checked_mul(e.x.mod_floor(&(m2 / e.gcd)))
+        .ok_or(GenericError)
+        .context(OVERFLOW)?
+        .mod_floor(&(m2 / e.gcd));
+    let a = m1
+        .checked_mul(k)
+        .and_then(|x| x.checked_add(a1))
+        .ok_or(GenericError)
+        .context(OVERFLOW)?;
+    Ok(Some((a.mod_floor(&lcm), lcm)))
+}
+
+/// The first step greater than zero at which all walks are at a goal at the
+/// same time, or `None` if that never happens.
+pub fn first_common_hit(cycles: &[Cycle]) -> Result<Option<usize>> {
+    let start = cycles
+        .iter()
+        .map(|c| c.tail)
+        .max()
+        .ok_or(GenericError)
+        .context("There are no walks to follow")?;
+
+    // Before all walks are in their cycle it is quickest to check every step
+    if let Some(step) = (1..start).find(|s| cycles.iter().all(|c| c.is_hit(*s))) {
+        return Ok(Some(step));
+    }
+
+    // Afterwards the walks are merged one at a time, keeping the distinct
+    // steps modulo the combined cycle length at which all of them so far
+    // are at a goal
+    let mut residues = BTreeSet::from([0]);
+    let mut modulus = 1;
+    for cycle in cycles {
+        let length = cycle.length as i128;
+        let mut merged = BTreeSet::new();
+        for residue in &residues {
+            for hit in &cycle.cycle_hits {
+                if let Some((a, _)) = crt((*residue, modulus), (*hit as i128, length))? {
+                    merged.insert(a);
+                }
+            }
+        }
+
+        if merged.is_empty() {
+            return Ok(None);
+        }
+        residues = merged;
+        modulus = modulus.lcm(&length);
+    }
+
+    let start = start.max(1) as i128;
+    residues
+        .into_iter()
+        .map(|a| {
+            Integer::div_ceil(&(start - a), &modulus)
+                .max(0)
+                .checked_mul(modulus)
+                .and_then(|x| x.checked_add(a))
+                .and_then(|step| usize::try_from(step).ok())
+                .ok_or(GenericError)
+                .context("The meeting step does not fit into a usize")
+        })
+        .collect::<Result<Vec<_>>>()
+        .map(|steps| steps.into_iter().min())
+}
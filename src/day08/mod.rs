@@ -1,72 +1,151 @@
+mod cycle;
+mod selector;
+
 use anyhow::{Context, Error, Result};
-use itertools::Itertools;
-use std::str::FromStr;
+use std::{collections::HashMap, fmt, str::FromStr};
+
+use crate::{explain::Explanation, utils::AocError::*};
 
-use crate::utils::AocError::*;
+pub use cycle::{crt, first_common_hit, Cycle};
+pub use selector::Selector;
 
+/// The map with every node label replaced by its index, so a step is a
+/// lookup in the adjacency table.
 #[derive(Debug)]
 pub struct Navigation {
-    path: Vec<char>,
-    nodes: Vec<Node>,
+    // 0 for left and 1 for right
+    path: Vec<usize>,
+    labels: Vec<String>,
+    index: HashMap<String, usize>,
+    links: Vec<[usize; 2]>,
 }
 
 impl Navigation {
     pub fn find(&self, node: &str) -> Option<usize> {
-        self.nodes
-            .iter()
-            .find_position(|n| n.id == node)
-            .map(|(p, _)| p)
+        self.index.get(node).copied()
+    }
+
+    pub fn label(&self, node: usize) -> &str {
+        &self.labels[node]
     }
 
-    pub fn follow(&self, start: &str, zzz: bool) -> Option<usize> {
-        let mut current = start;
-        let mut index = self.find(current)?;
-        let mut instructions = self.path.iter().cycle();
-        let mut counter = 0;
+    /// The node reached from `node` with the instruction at position `step`
+    /// of the (repeating) path.
+    pub fn step(&self, node: usize, step: usize) -> usize {
+        self.links[node][self.path[step % self.path.len()]]
+    }
+
+    /// All nodes with a label that matches the selector.
+    pub fn select(&self, selector: &Selector) -> Vec<usize> {
+        (0..self.labels.len())
+            .filter(|n| selector.matches(self.label(*n)))
+            .collect()
+    }
+
+    /// The nodes visited from `start` up to the first node that matches the
+    /// goal, after at least one step. If a node is visited twice at the same
+    /// position in the path without reaching the goal, it can never be
+    /// reached and the result is `None`.
+    pub fn path(&self, start: usize, goal: &Selector) -> Option<Vec<usize>> {
+        let len = self.path.len();
+        let mut seen = vec![false; self.labels.len() * len];
+        let mut path = vec![start];
+        let mut node = start;
 
         loop {
-            counter += 1;
-            let direction = instructions.next()?;
-            let node = self.nodes.get(index)?;
-            current = match direction {
-                'L' => &node.left,
-                'R' => &node.right,
-                _ => None?,
-            };
+            let step = path.len() - 1;
+            let state = node * len + step % len;
+            if seen[state] {
+                return None;
+            }
+            seen[state] = true;
 
-            if zzz && current == "ZZZ" {
-                break;
+            node = self.step(node, step);
+            path.push(node);
+            if goal.matches(self.label(node)) {
+                return Some(path);
             }
+        }
+    }
 
-            if !zzz && ends_with(current, 'Z') {
-                break;
+    /// The number of steps from `start` to the goal.
+    pub fn follow(&self, start: &str, goal: &Selector) -> Result<usize> {
+        let node = self
+            .find(start)
+            .ok_or(GenericError)
+            .with_context(|| format!("Unknown node {}", start))?;
+        let path = self
+            .path(node, goal)
+            .ok_or(GenericError)
+            .with_context(|| format!("Can not reach {:?} from {}", goal, start))?;
+        Ok(path.len() - 1)
+    }
+
+    /// Walk from `start` until a state of node and position in the path
+    /// repeats and note every step at which `goal` holds for the node.
+    pub fn cycle<F: Fn(usize) -> bool>(&self, start: usize, goal: F) -> Cycle {
+        let len = self.path.len();
+        let mut seen = vec![None; self.labels.len() * len];
+        let mut hits = vec![];
+        let mut node = start;
+        let mut step = 0;
+
+        let tail = loop {
+            let state = node * len + step % len;
+            if let Some(first) = seen[state] {
+                break first;
             }
+            seen[state] = Some(step);
 
-            index = self.find(current)?;
+            if goal(node) {
+                hits.push(step);
+            }
+            node = self.step(node, step);
+            step += 1;
+        };
+
+        let (tail_hits, cycle_hits) = hits.into_iter().partition(|h| *h < tail);
+        Cycle {
+            tail,
+            length: step - tail,
+            tail_hits,
+            cycle_hits,
         }
-
-        Some(counter)
     }
 
-    pub fn follow_parallel(&self) -> Option<usize> {
-        let all_ends_with_a = self
-            .nodes
-            .iter()
-            .filter_map(|n| {
-                if ends_with(&n.id, 'A') {
-                    Some(&n.id)
-                } else {
-                    None
-                }
-            })
+    /// The number of steps until the walks from all start nodes are on a
+    /// goal node at the same time.
+    pub fn follow_all(&self, starts: &Selector, goal: &Selector) -> Result<Meeting> {
+        let cycles = self
+            .select(starts)
+            .into_iter()
+            .map(|n| self.cycle(n, |m| goal.matches(self.label(m))))
             .collect::<Vec<_>>();
-        let len = all_ends_with_a
-            .iter()
-            .map(|n| self.follow(n, false))
-            .collect::<Option<Vec<_>>>()?;
-        let result = len.iter().fold(1, |acc, el| num::Integer::lcm(&acc, el));
 
-        Some(result)
+        if cycles.is_empty() {
+            return Err(GenericError).context("There are no start nodes");
+        }
+
+        Ok(match first_common_hit(&cycles)? {
+            Some(step) => Meeting::At(step),
+            None => Meeting::Never,
+        })
+    }
+}
+
+/// When the ghosts all reach their goal at once.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Meeting {
+    At(usize),
+    Never,
+}
+
+impl fmt::Display for Meeting {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Meeting::At(step) => write!(f, "{}", step),
+            Meeting::Never => write!(f, "never"),
+        }
     }
 }
 
@@ -80,18 +159,51 @@ impl FromStr for Navigation {
             .next()
             .ok_or(GenericError)
             .context("Cannot split path")?
+            .trim()
             .chars()
-            .collect::<Vec<_>>();
+            .map(|c| match c {
+                'L' => Ok(0),
+                'R' => Ok(1),
+                _ => Err(GenericError).with_context(|| format!("Unknown direction {}", c)),
+            })
+            .collect::<Result<Vec<_>>>()?;
+        if path.is_empty() {
+            return Err(GenericError).context("Path is empty");
+        }
 
         let nodes = split
             .next()
             .ok_or(GenericError)
             .context("Could not parse nodes")?
             .lines()
+            .filter(|l| !l.is_empty())
             .map(Node::from_str)
             .collect::<Result<Vec<_>>>()?;
 
-        Ok(Navigation { path, nodes })
+        let labels = nodes.iter().map(|n| n.id.clone()).collect::<Vec<_>>();
+        let index = labels
+            .iter()
+            .enumerate()
+            .map(|(i, l)| (l.clone(), i))
+            .collect::<HashMap<_, _>>();
+        let lookup = |label: &str| {
+            index
+                .get(label)
+                .copied()
+                .ok_or(GenericError)
+                .with_context(|| format!("Unknown node {}", label))
+        };
+        let links = nodes
+            .iter()
+            .map(|n| Ok([lookup(&n.left)?, lookup(&n.right)?]))
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Navigation {
+            path,
+            labels,
+            index,
+            links,
+        })
     }
 }
 
@@ -140,20 +252,83 @@ pub fn input_generator(input: &str) -> Result<Navigation> {
 
 #[aoc(day08, part1)]
 pub fn solve_part1(input: &Navigation) -> Result<usize> {
-    let result = input
-        .follow("AAA", true)
-        .ok_or(GenericError)
-        .context("Follow failed")?;
-    Ok(result)
+    input.follow("AAA", &Selector::set(&["ZZZ"]))
 }
 
 #[aoc(day08, part2)]
-pub fn solve_part2(input: &Navigation) -> Result<usize> {
-    let result = input
-        .follow_parallel()
+pub fn solve_part2(input: &Navigation) -> Result<Meeting> {
+    input.follow_all(
+        &Selector::predicate(|l| ends_with(l, 'A')),
+        &Selector::predicate(|l| ends_with(l, 'Z')),
+    )
+}
+
+pub fn explain_part1(input: &Navigation) -> Result<Explanation> {
+    let start = input
+        .find("AAA")
         .ok_or(GenericError)
-        .context("Follow failed")?;
-    Ok(result)
+        .context("Unknown node AAA")?;
+    let path = input
+        .path(start, &Selector::set(&["ZZZ"]))
+        .ok_or(GenericError)
+        .context("Can not reach ZZZ from AAA")?;
+
+    let rows = path
+        .windows(2)
+        .enumerate()
+        .map(|(step, pair)| {
+            let turn = match input.path[step % input.path.len()] {
+                0 => "L",
+                _ => "R",
+            };
+            vec![
+                (step + 1).to_string(),
+                input.label(pair[0]).to_string(),
+                turn.to_string(),
+                input.label(pair[1]).to_string(),
+            ]
+        })
+        .collect();
+
+    Ok(Explanation::new(
+        &["step", "from", "turn", "to"],
+        rows,
+        path.len() - 1,
+    ))
+}
+
+pub fn explain_part2(input: &Navigation) -> Result<Explanation> {
+    let goal = Selector::predicate(|l| ends_with(l, 'Z'));
+    let rows = input
+        .select(&Selector::predicate(|l| ends_with(l, 'A')))
+        .into_iter()
+        .map(|n| {
+            let cycle = input.cycle(n, |m| goal.matches(input.label(m)));
+            let hits = cycle
+                .tail_hits
+                .iter()
+                .map(|h| h.to_string())
+                .chain(
+                    cycle
+                        .cycle_hits
+                        .iter()
+                        .map(|h| format!("{}+{}n", h, cycle.length)),
+                )
+                .collect::<Vec<_>>();
+            vec![
+                input.label(n).to_string(),
+                cycle.tail.to_string(),
+                cycle.length.to_string(),
+                hits.join(", "),
+            ]
+        })
+        .collect();
+
+    Ok(Explanation::new(
+        &["start", "tail", "cycle", "goal steps"],
+        rows,
+        solve_part2(input)?,
+    ))
 }
 
 #[cfg(test)]
@@ -190,6 +365,17 @@ ZZZ = (ZZZ, ZZZ)"
         Ok(assert_eq!(2, solve_part1(&data)?))
     }
 
+    #[test]
+    fn part1_explain() -> Result<()> {
+        let data = input(sample2())?;
+        let explanation = explain_part1(&data)?;
+        assert_eq!("6", explanation.answer);
+        Ok(assert_eq!(
+            vec!["6", "BBB", "R", "ZZZ"],
+            explanation.rows[5]
+        ))
+    }
+
     #[test]
     fn part1_sample2() -> Result<()> {
         let data = input(sample2())?;
@@ -209,9 +395,131 @@ ZZZ = (ZZZ, ZZZ)"
 XXX = (XXX, XXX)"
     }
 
+    #[test]
+    fn adjacency_table() -> Result<()> {
+        let data = input(sample2())?;
+        let aaa = data.find("AAA").ok_or(GenericError)?;
+        let bbb = data.step(aaa, 0);
+        assert_eq!("BBB", data.label(bbb));
+        assert_eq!("ZZZ", data.label(data.step(bbb, 5)));
+        assert!(input("LR\n\nAAA = (BBB, AAA)").is_err());
+        Ok(assert!(input("LX\n\nAAA = (AAA, AAA)").is_err()))
+    }
+
     #[test]
     fn part2_sample3() -> Result<()> {
         let data = input(sample3())?;
-        Ok(assert_eq!(6, solve_part2(&data)?))
+        Ok(assert_eq!(Meeting::At(6), solve_part2(&data)?))
+    }
+
+    #[test]
+    fn part2_explain() -> Result<()> {
+        let data = input(sample3())?;
+        let explanation = explain_part2(&data)?;
+        assert_eq!("6", explanation.answer);
+        assert_eq!(vec!["11A", "1", "2", "2+2n"], explanation.rows[0]);
+        Ok(assert_eq!(
+            vec!["22A", "1", "6", "3+6n, 6+6n"],
+            explanation.rows[1]
+        ))
+    }
+
+    #[test]
+    fn ghosts_with_tails() -> Result<()> {
+        // 11A reaches 11Z after 2 and then every 3 steps, 22A after 1 and
+        // then every 2 steps, so they first meet after 5 steps.
+        let data = input(
+            "L
+
+11A = (11B, 11B)
+11B = (11Z, 11Z)
+11Z = (11C, 11C)
+11C = (11D, 11D)
+11D = (11Z, 11Z)
+22A = (22Z, 22Z)
+22Z = (22B, 22B)
+22B = (22Z, 22Z)",
+        )?;
+        assert_eq!(Meeting::At(5), solve_part2(&data)?);
+
+        // 11A is at its goal after odd and 22A after even numbers of steps
+        let never = input(
+            "L
+
+11A = (11Z, 11Z)
+11Z = (11B, 11B)
+11B = (11Z, 11Z)
+22A = (22B, 22B)
+22B = (22Z, 22Z)
+22Z = (22B, 22B)",
+        )?;
+        Ok(assert_eq!("never", solve_part2(&never)?.to_string()))
+    }
+
+    #[test]
+    fn paths() -> Result<()> {
+        let data = input(sample1())?;
+        let aaa = data.find("AAA").ok_or(GenericError)?;
+        let path = data
+            .path(aaa, &Selector::regex("^Z+$")?)
+            .ok_or(GenericError)?;
+        assert_eq!(
+            vec!["AAA", "CCC", "ZZZ"],
+            path.iter().map(|n| data.label(*n)).collect::<Vec<_>>()
+        );
+        assert_eq!(1, data.follow("AAA", &Selector::set(&["BBB", "CCC"]))?);
+
+        // DDD, EEE and GGG only lead back to themselves
+        assert_eq!(None, data.path(aaa, &Selector::set(&["FFF"])));
+        assert!(data.follow("DDD", &Selector::set(&["ZZZ"])).is_err());
+
+        let starts = Selector::predicate(|l| l.starts_with('A') || l.starts_with('C'));
+        assert_eq!(
+            Meeting::At(1),
+            data.follow_all(&starts, &Selector::regex("^[CG]")?)?
+        );
+        assert_eq!(
+            Meeting::Never,
+            data.follow_all(&starts, &Selector::set(&["DDD"]))?
+        );
+        Ok(assert!(data
+            .follow_all(&Selector::set(&["XYZ"]), &Selector::set(&["ZZZ"]))
+            .is_err()))
+    }
+
+    #[test]
+    fn generalized_crt() -> Result<()> {
+        assert_eq!(Some((5, 12)), crt((1, 4), (5, 6))?);
+        assert_eq!(None, crt((0, 4), (1, 6))?);
+        Ok(assert!(crt((0, i128::MAX), (1, i128::MAX - 1)).is_err()))
+    }
+
+    #[test]
+    fn many_hits() -> Result<()> {
+        // Ten walks with hits at every step but one of their cycle would
+        // make 10^10 systems when combined naively
+        let cycles = (0..10)
+            .map(|i| Cycle {
+                tail: 0,
+                length: 11,
+                tail_hits: vec![],
+                cycle_hits: (0..11).filter(|h| *h != i).collect(),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(Some(10), first_common_hit(&cycles)?);
+
+        // Coprime cycle lengths around 2^62 overflow the combined length
+        let n = 1 << 62;
+        let huge = [n - 1, n, n + 1]
+            .into_iter()
+            .map(|length| Cycle {
+                tail: 0,
+                length,
+                tail_hits: vec![],
+                cycle_hits: vec![0],
+            })
+            .collect::<Vec<_>>();
+        assert!(first_common_hit(&huge).is_err());
+        Ok(assert!(first_common_hit(&[]).is_err()))
     }
 }
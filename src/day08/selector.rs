@@ -0,0 +1,43 @@
+use anyhow::{Context, Result};
+use regex::Regex;
+use std::{collections::HashSet, fmt};
+
+/// A set of nodes given by their labels, used for start and goal nodes.
+pub enum Selector {
+    Set(HashSet<String>),
+    Regex(Regex),
+    Predicate(Box<dyn Fn(&str) -> bool>),
+}
+
+impl Selector {
+    pub fn set(labels: &[&str]) -> Self {
+        Selector::Set(labels.iter().map(|l| l.to_string()).collect())
+    }
+
+    pub fn regex(re: &str) -> Result<Self> {
+        let re = Regex::new(re).with_context(|| format!("Invalid pattern {}", re))?;
+        Ok(Selector::Regex(re))
+    }
+
+    pub fn predicate<F: Fn(&str) -> bool + 'static>(f: F) -> Self {
+        Selector::Predicate(Box::new(f))
+    }
+
+    pub fn matches(&self, label: &str) -> bool {
+        match self {
+            Selector::Set(labels) => labels.contains(label),
+            Selector::Regex(re) => re.is_match(label),
+            Selector::Predicate(f) => f(label),
+        }
+    }
+}
+
+impl fmt::Debug for Selector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Selector::Set(labels) => write!(f, "Set({:?})", labels),
+            Selector::Regex(re) => write!(f, "Regex({})", re),
+            Selector::Predicate(_) => write!(f, "Predicate"),
+        }
+    }
+}
@@ -1,76 +1,200 @@
 use anyhow::{Context, Result};
+use num::{rational::Ratio, BigInt};
+use std::io::BufRead;
+
+use crate::{
+    explain::Explanation,
+    utils::{read_lines, AocError::*},
+};
+
+fn parse_line(l: &str) -> Result<Vec<i64>> {
+    l.split(' ')
+        .map(|v| Ok(v.parse::<i64>()?))
+        .collect::<Result<Vec<_>>>()
+}
 
 #[aoc_generator(day09)]
 pub fn input_generator(input: &str) -> Result<Vec<Vec<i64>>> {
     input
         .lines()
         .filter(|s| !s.is_empty())
-        .map(|l| {
-            l.split(' ')
-                .map(|v| Ok(v.parse::<i64>()?))
-                .collect::<Result<Vec<_>>>()
-        })
+        .map(parse_line)
         .collect::<Result<Vec<_>>>()
         .context("Error while parsing input")
 }
 
-fn next_value(row: &Vec<i64>) -> Option<i64> {
-    let mut diffs = vec![row.clone()];
-    loop {
-        let next = diffs
-            .iter()
-            .last()?
-            .windows(2)
-            .map(|w| w[1] - w[0])
-            .collect::<Vec<_>>();
+pub fn input_reader<R: BufRead>(reader: R) -> impl Iterator<Item = Result<Vec<i64>>> {
+    read_lines(reader, parse_line)
+}
+
+/// A sequence continued by the polynomial of the lowest degree that goes
+/// through all of its values.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Extrapolation {
+    pub value: i128,
+    pub degree: usize,
+    differences: Vec<i128>,
+}
 
-        if next.iter().all(|v| *v == 0) {
-            diffs.push(next);
-            let lasts = diffs
-                .iter()
-                .map(|v| v.iter().last())
-                .collect::<Option<Vec<_>>>()?;
+impl Extrapolation {
+    /// The coefficients of the polynomial, the constant first. The first
+    /// value of the sequence is at position 0.
+    ///
+    /// Expands the falling factorials `x (x - 1) ... (x - j + 1) / j!` of
+    /// the Newton form. They outgrow machine integers quickly, e.g. `34!`
+    /// does not fit into an `i128`, so they are only computed on request.
+    pub fn coefficients(&self) -> Vec<Ratio<BigInt>> {
+        let mut coefficients = vec![Ratio::from_integer(BigInt::from(0))];
+        let mut falling = vec![BigInt::from(1)];
+        let mut factorial = BigInt::from(1);
+        for (j, d) in self.differences.iter().enumerate() {
+            coefficients.resize(falling.len(), Ratio::from_integer(BigInt::from(0)));
+            for (c, f) in coefficients.iter_mut().zip(&falling) {
+                *c += Ratio::new(f * d, factorial.clone());
+            }
 
-            return Some(lasts.iter().map(|v| **v).sum::<i64>());
-        } else {
-            diffs.push(next);
+            let mut next = vec![BigInt::from(0); falling.len() + 1];
+            for (i, f) in falling.iter().enumerate() {
+                next[i + 1] += f;
+                next[i] -= f * j;
+            }
+            falling = next;
+            factorial *= j + 1;
         }
+
+        coefficients
     }
 }
 
-#[aoc(day09, part1)]
-pub fn solve_part1(input: &[Vec<i64>]) -> Result<i64> {
-    let result = input.iter().filter_map(next_value).sum::<i64>();
-    Ok(result)
-}
+// The first value of every row of the difference pyramid up to the last
+// row that is not all zeros, or `None` if a difference overflows.
+fn leading_differences(seq: &[i64]) -> Option<Vec<i128>> {
+    let mut row = seq.iter().map(|v| *v as i128).collect::<Vec<_>>();
+    let mut result = vec![];
 
-fn previous_value(row: &Vec<i64>) -> Option<i64> {
-    let mut diffs = vec![row.clone()];
-    loop {
-        let next = diffs
-            .iter()
-            .last()?
+    while row.iter().any(|v| *v != 0) {
+        result.push(row[0]);
+        row = row
             .windows(2)
-            .map(|w| w[1] - w[0])
-            .collect::<Vec<_>>();
-        if next.iter().all(|v| *v == 0) {
-            diffs.push(next);
-            let lasts = diffs
-                .iter()
-                .map(|v| v.iter().rev().last())
-                .collect::<Option<Vec<_>>>()?;
-
-            return Some(lasts.iter().rev().map(|v| **v).fold(0, |acc, el| el - acc));
-        } else {
-            diffs.push(next);
+            .map(|w| w[1].checked_sub(w[0]))
+            .collect::<Option<_>>()?;
+    }
+
+    Some(result)
+}
+
+/// Predict the value `k` steps after the end of the sequence, or `-k` steps
+/// before its start if `k` is negative. `k = 0` is the last value.
+///
+/// Uses Newton's forward differences, `p(x) = Σ Δʲy₀ · C(x, j)`, which is
+/// exact in integers because every binomial coefficient `C(x, j)` is an
+/// integer, also for negative `x`. Returns `None` for an empty sequence or
+/// if the value does not fit into an `i128`.
+pub fn extrapolate(seq: &[i64], k: i64) -> Option<Extrapolation> {
+    if seq.is_empty() {
+        return None;
+    }
+
+    let x = if k >= 0 {
+        seq.len() as i128 - 1 + k as i128
+    } else {
+        k as i128
+    };
+    let differences = leading_differences(seq)?;
+
+    // C(x, j) = C(x, j - 1) (x - j + 1) / j, the division is exact
+    let mut value = 0_i128;
+    let mut binomial = 1_i128;
+    for (j, d) in differences.iter().enumerate() {
+        if j > 0 {
+            binomial = binomial.checked_mul(x - (j as i128 - 1))? / j as i128;
         }
+        value = value.checked_add(d.checked_mul(binomial)?)?;
     }
+
+    Some(Extrapolation {
+        value,
+        degree: differences.len().max(1) - 1,
+        differences,
+    })
+}
+
+fn next_value(row: &[i64]) -> Result<i64> {
+    extrapolate(row, 1)
+        .and_then(|e| i64::try_from(e.value).ok())
+        .ok_or(GenericError)
+        .context("The next value does not fit into an i64")
+}
+
+fn previous_value(row: &[i64]) -> Result<i64> {
+    extrapolate(row, -1)
+        .and_then(|e| i64::try_from(e.value).ok())
+        .ok_or(GenericError)
+        .context("The previous value does not fit into an i64")
+}
+
+/// Add the value extrapolated from the `i`th row to the total.
+fn add_row(total: i64, i: usize, value: Result<i64>) -> Result<i64> {
+    let value = value.with_context(|| format!("Row {} overflows i64", i + 1))?;
+    total
+        .checked_add(value)
+        .ok_or(GenericError)
+        .context("The sum overflows i64")
+}
+
+#[aoc(day09, part1)]
+pub fn solve_part1(input: &[Vec<i64>]) -> Result<i64> {
+    input
+        .iter()
+        .enumerate()
+        .try_fold(0, |acc, (i, row)| add_row(acc, i, next_value(row)))
 }
 
 #[aoc(day09, part2)]
 pub fn solve_part2(input: &[Vec<i64>]) -> Result<i64> {
-    let result = input.iter().filter_map(previous_value).sum::<i64>();
-    Ok(result)
+    input
+        .iter()
+        .enumerate()
+        .try_fold(0, |acc, (i, row)| add_row(acc, i, previous_value(row)))
+}
+
+fn explain(input: &[Vec<i64>], extrapolate: fn(&[i64]) -> Result<i64>) -> Result<Explanation> {
+    let mut total = 0;
+    let mut rows = vec![];
+    for (i, row) in input.iter().enumerate() {
+        let value = extrapolate(row);
+        let shown = value.as_ref().map_or("-".to_string(), |v| v.to_string());
+        total = add_row(total, i, value)?;
+        rows.push(vec![
+            row.iter()
+                .map(|v| v.to_string())
+                .collect::<Vec<_>>()
+                .join(" "),
+            shown,
+        ]);
+    }
+
+    Ok(Explanation::new(&["sequence", "extrapolated"], rows, total))
+}
+
+pub fn explain_part1(input: &[Vec<i64>]) -> Result<Explanation> {
+    explain(input, next_value)
+}
+
+pub fn explain_part2(input: &[Vec<i64>]) -> Result<Explanation> {
+    explain(input, previous_value)
+}
+
+pub fn stream_part1<R: BufRead>(reader: R) -> Result<i64> {
+    input_reader(reader)
+        .enumerate()
+        .try_fold(0, |acc, (i, row)| add_row(acc, i, next_value(&row?)))
+}
+
+pub fn stream_part2<R: BufRead>(reader: R) -> Result<i64> {
+    input_reader(reader)
+        .enumerate()
+        .try_fold(0, |acc, (i, row)| add_row(acc, i, previous_value(&row?)))
 }
 
 #[cfg(test)]
@@ -98,4 +222,74 @@ mod test {
         let data = input()?;
         Ok(assert_eq!(2, solve_part2(&data)?))
     }
+
+    #[test]
+    fn part1_stream() -> Result<()> {
+        Ok(assert_eq!(114, stream_part1(sample().as_bytes())?))
+    }
+
+    fn ratio(numer: i64, denom: i64) -> Ratio<BigInt> {
+        Ratio::new(BigInt::from(numer), BigInt::from(denom))
+    }
+
+    #[test]
+    fn extrapolation() -> Result<()> {
+        let data = input()?;
+        // 1 3 6 10 15 21 are the triangular numbers (x + 1)(x + 2) / 2
+        let triangular = extrapolate(&data[1], 3).ok_or(GenericError)?;
+        assert_eq!(45, triangular.value);
+        assert_eq!(2, triangular.degree);
+        assert_eq!(
+            vec![ratio(1, 1), ratio(3, 2), ratio(1, 2)],
+            triangular.coefficients()
+        );
+        assert_eq!(Some(0), extrapolate(&data[1], -2).map(|e| e.value));
+        assert_eq!(Some(1), extrapolate(&data[1], -3).map(|e| e.value));
+
+        assert_eq!(Some(-6), extrapolate(&data[0], -2).map(|e| e.value));
+        assert_eq!(Some(45), extrapolate(&data[2], 0).map(|e| e.value));
+
+        let zeros = extrapolate(&[0, 0, 0], 5).ok_or(GenericError)?;
+        assert_eq!((0, 0), (zeros.value, zeros.degree));
+        Ok(assert_eq!(None, extrapolate(&[], 1)))
+    }
+
+    #[test]
+    fn high_degrees() -> Result<()> {
+        // C(x, 35) for x = 0..40 is a polynomial of degree 35, whose
+        // coefficients have 35! in the denominator
+        let binomials = (0..40_i64)
+            .map(|x| (0..35).fold(1_i128, |acc, j| acc * (x as i128 - j) / (j + 1)) as i64)
+            .collect::<Vec<_>>();
+        assert_eq!(658008, solve_part1(std::slice::from_ref(&binomials))?);
+
+        let fit = extrapolate(&binomials, 1).ok_or(GenericError)?;
+        assert_eq!(35, fit.degree);
+        let coefficients = fit.coefficients();
+        let leading = (1..=35).fold(BigInt::from(1), |acc, j| acc * j);
+        assert_eq!(Ratio::new(BigInt::from(1), leading), coefficients[35]);
+        Ok(assert_eq!(36, coefficients.len()))
+    }
+
+    #[test]
+    fn overflowing_values() {
+        let powers = (0..12).map(|i| 1 << i).collect::<Vec<_>>();
+        // The polynomial through 1, 2, 4, ..., 2048 continues with 2¹² - 1
+        assert_eq!(Some(4095), extrapolate(&powers, 1).map(|e| e.value));
+        assert_eq!(None, extrapolate(&powers, 1_000_000_000_000));
+    }
+
+    #[test]
+    fn overflowing_rows() -> Result<()> {
+        let rows = "0 3 6\n0 9223372036854775807\n";
+        let data = input_generator(rows)?;
+        let error = solve_part1(&data).unwrap_err();
+        assert_eq!("Row 2 overflows i64", error.to_string());
+        assert!(stream_part1(rows.as_bytes()).is_err());
+        assert!(explain_part1(&data).is_err());
+
+        // Every row fits, but their sum does not
+        let data = input_generator("0 9223372036854775806\n0 9223372036854775806")?;
+        Ok(assert!(solve_part2(&data).is_err()))
+    }
 }
@@ -1,7 +1,7 @@
 use anyhow::{Context, Result};
 use std::collections::HashMap;
 
-use crate::utils::AocError::*;
+use crate::{explain::Explanation, utils::AocError::*};
 
 type Coords = (usize, usize);
 
@@ -165,8 +165,8 @@ pub fn is_start_relevant(path: &[Coords]) -> bool {
     }
 }
 
-#[aoc(day10, part2)]
-pub fn solve_part2(input: &HashMap<Coords, char>) -> Result<usize> {
+/// The number of tiles enclosed by the loop in every row of the map.
+fn enclosed_per_row(input: &HashMap<Coords, char>) -> Result<Vec<usize>> {
     let path = path(input)
         .ok_or(GenericError)
         .context("Could not determine path")?;
@@ -179,20 +179,70 @@ pub fn solve_part2(input: &HashMap<Coords, char>) -> Result<usize> {
     }
 
     let mut is_inside = false;
-    let mut counter = 0;
-    map.iter().for_each(|l| {
-        l.iter().for_each(|c| {
-            if relevant_pipes.contains(c) {
-                is_inside = !is_inside;
-            }
+    let counters = map
+        .iter()
+        .map(|l| {
+            let mut counter = 0;
+            l.iter().for_each(|c| {
+                if relevant_pipes.contains(c) {
+                    is_inside = !is_inside;
+                }
 
-            if *c == '.' && is_inside {
-                counter += 1;
-            }
-        });
-    });
+                if *c == '.' && is_inside {
+                    counter += 1;
+                }
+            });
+            counter
+        })
+        .collect();
+
+    Ok(counters)
+}
 
-    Ok(counter)
+#[aoc(day10, part2)]
+pub fn solve_part2(input: &HashMap<Coords, char>) -> Result<usize> {
+    Ok(enclosed_per_row(input)?.iter().sum())
+}
+
+pub fn explain_part1(input: &HashMap<Coords, char>) -> Result<Explanation> {
+    let path = path(input)
+        .ok_or(GenericError)
+        .context("Could not determine path")?;
+    let len = path.len() - 1;
+
+    let rows = path[..len]
+        .iter()
+        .enumerate()
+        .map(|(step, pos)| {
+            vec![
+                step.to_string(),
+                format!("{},{}", pos.1 + 1, pos.0 + 1),
+                input.get(pos).copied().unwrap_or('.').to_string(),
+                step.min(len - step).to_string(),
+            ]
+        })
+        .collect();
+
+    Ok(Explanation::new(
+        &["step", "tile", "pipe", "distance"],
+        rows,
+        len / 2,
+    ))
+}
+
+pub fn explain_part2(input: &HashMap<Coords, char>) -> Result<Explanation> {
+    let counters = enclosed_per_row(input)?;
+    let rows = counters
+        .iter()
+        .enumerate()
+        .map(|(y, c)| vec![(y + 1).to_string(), c.to_string()])
+        .collect();
+
+    Ok(Explanation::new(
+        &["row", "enclosed"],
+        rows,
+        counters.iter().sum::<usize>(),
+    ))
 }
 
 #[cfg(test)]
@@ -225,6 +275,15 @@ LJ..."
         Ok(assert_eq!(4, solve_part1(&data)?))
     }
 
+    #[test]
+    fn part1_explain() -> Result<()> {
+        let data = input(sample1())?;
+        let explanation = explain_part1(&data)?;
+        assert_eq!("4", explanation.answer);
+        assert_eq!(8, explanation.rows.len());
+        Ok(assert_eq!(vec!["4", "4,4", "J", "4"], explanation.rows[4]))
+    }
+
     #[test]
     fn part1_sample2() -> Result<()> {
         let data = input(sample2())?;
@@ -269,6 +328,14 @@ L.L7LFJ|||||FJL7||LJ
 L7JLJL-JLJLJL--JLJ.L"
     }
 
+    #[test]
+    fn part2_explain() -> Result<()> {
+        let data = input(sample3())?;
+        let explanation = explain_part2(&data)?;
+        assert_eq!("4", explanation.answer);
+        Ok(assert_eq!(vec!["7", "4"], explanation.rows[6]))
+    }
+
     #[test]
     fn part2_sample3() -> Result<()> {
         let data = input(sample3())?;
@@ -2,7 +2,7 @@ use anyhow::Result;
 use itertools::Itertools;
 use std::cmp::{max, min};
 
-use crate::utils::AocError::*;
+use crate::{explain::Explanation, utils::AocError::*};
 
 #[aoc_generator(day11)]
 pub fn input_generator(input: &str) -> Result<Vec<Vec<char>>> {
@@ -94,6 +94,42 @@ fn solve(m: &[Vec<char>], age: usize) -> Result<usize> {
     Ok(sum)
 }
 
+fn explain(m: &[Vec<char>], age: usize) -> Result<Explanation> {
+    let galaxies = find_galaxies(m);
+    let empty = find_empty(m).ok_or(GenericError)?;
+
+    let position = |(y, x): &Coords| format!("{},{}", y + 1, x + 1);
+    let rows = galaxies
+        .iter()
+        .enumerate()
+        .combinations(2)
+        .map(|pair| {
+            let (a, from) = pair[0];
+            let (b, to) = pair[1];
+            vec![
+                format!("{}-{}", a + 1, b + 1),
+                position(from),
+                position(to),
+                special_distance(empty.clone(), from, to, age).to_string(),
+            ]
+        })
+        .collect();
+
+    Ok(Explanation::new(
+        &["pair", "from", "to", "distance"],
+        rows,
+        solve(m, age)?,
+    ))
+}
+
+pub fn explain_part1(input: &[Vec<char>]) -> Result<Explanation> {
+    explain(input, 2)
+}
+
+pub fn explain_part2(input: &[Vec<char>]) -> Result<Explanation> {
+    explain(input, 1_000_000)
+}
+
 #[aoc(day11, part1)]
 pub fn solve_part1(input: &[Vec<char>]) -> Result<usize> {
     solve(input, 2)
@@ -107,6 +143,7 @@ pub fn solve_part2(input: &[Vec<char>]) -> Result<usize> {
 #[cfg(test)]
 mod test {
     use super::*;
+    use anyhow::Context;
 
     fn sample() -> &'static str {
         "...#......
@@ -136,4 +173,22 @@ mod test {
         let data = input()?;
         Ok(assert_eq!(82000210, solve_part2(&data)?))
     }
+
+    #[test]
+    fn part1_explain() -> Result<()> {
+        let data = input()?;
+        let explanation = explain_part1(&data)?;
+        assert_eq!("374", explanation.answer);
+        assert_eq!(36, explanation.rows.len());
+        assert_eq!(vec!["1-2", "1,4", "2,8", "6"], explanation.rows[0]);
+        Ok(assert_eq!(
+            vec!["5-9", "6,2", "10,5", "9"],
+            explanation
+                .rows
+                .iter()
+                .find(|r| r[0] == "5-9")
+                .context("No pair 5-9")?
+                .clone()
+        ))
+    }
 }
@@ -1,9 +1,11 @@
 use anyhow::{Context, Error, Result};
 use itertools::Itertools;
-use memoize::memoize;
-use std::str::FromStr;
+use std::{io::BufRead, str::FromStr};
 
-use crate::utils::AocError::*;
+use crate::{
+    explain::Explanation,
+    utils::{read_lines, AocError::*},
+};
 
 #[derive(Debug)]
 pub struct SpringConfig {
@@ -79,56 +81,52 @@ pub fn input_generator(input: &str) -> Result<Vec<SpringConfig>> {
         .context("Error while parsing input")
 }
 
+pub fn input_reader<R: BufRead>(reader: R) -> impl Iterator<Item = Result<SpringConfig>> {
+    read_lines(reader, |l| SpringConfig::from_str(l.trim()))
+}
+
 #[aoc(day12, part1)]
 pub fn solve_part1(input: &[SpringConfig]) -> Result<usize> {
     Ok(input
         .iter()
-        .map(|s| count_possible_solutions(s.springs.clone(), 0, s.config.clone()))
+        .map(|s| count_possible_solutions(&s.springs, &s.config))
         .sum::<usize>())
 }
 
-#[memoize]
-fn count_possible_solutions(springs: Vec<char>, matches: usize, config: Vec<usize>) -> usize {
-    if springs.is_empty() && matches == 0 && config.is_empty() {
-        return 1;
-    }
-
-    if springs.is_empty() && config.len() == 1 && matches == config[0] {
-        return 1;
-    }
+/// Counts the possible arrangements with a table indexed by the remaining
+/// springs and the remaining groups.
+fn count_possible_solutions(springs: &[char], config: &[usize]) -> usize {
+    let n = springs.len();
+    let m = config.len();
 
-    if springs.is_empty() {
-        return 0;
-    }
+    // ways[i][j] is the number of arrangements of springs[i..] for config[j..]
+    let mut ways = vec![vec![0; m + 1]; n + 2];
+    ways[n][m] = 1;
+    ways[n + 1][m] = 1;
 
-    if matches > 0 && config.is_empty() {
-        return 0;
-    }
+    for i in (0..n).rev() {
+        for j in (0..=m).rev() {
+            let mut count = 0;
 
-    match (springs[0], matches) {
-        ('?', 0) => {
-            count_possible_solutions(springs[1..].to_vec(), 1, config.clone())
-                + count_possible_solutions(springs[1..].to_vec(), 0, config.clone())
-        }
-        ('?', c) => {
-            let mut sub_count =
-                count_possible_solutions(springs[1..].to_vec(), c + 1, config.clone());
-            if c == config[0] {
-                sub_count +=
-                    count_possible_solutions(springs[1..].to_vec(), 0, config[1..].to_vec());
+            if springs[i] != '#' {
+                count += ways[i + 1][j];
             }
-            sub_count
-        }
-
-        ('#', 0) => count_possible_solutions(springs[1..].to_vec(), 1, config),
-        ('#', c) => count_possible_solutions(springs[1..].to_vec(), c + 1, config),
 
-        ('.', 0) => count_possible_solutions(springs[1..].to_vec(), 0, config),
-        ('.', x) if x != config[0] => 0,
-        ('.', _) => count_possible_solutions(springs[1..].to_vec(), 0, config[1..].to_vec()),
+            if springs[i] != '.' && j < m {
+                let end = i + config[j];
+                if end <= n
+                    && springs[i..end].iter().all(|c| *c != '.')
+                    && springs.get(end) != Some(&'#')
+                {
+                    count += ways[end + 1][j + 1];
+                }
+            }
 
-        _ => panic!("No way"),
+            ways[i][j] = count;
+        }
     }
+
+    ways[0][0]
 }
 
 #[aoc(day12, part2)]
@@ -136,11 +134,59 @@ pub fn solve_part2(input: &[SpringConfig]) -> Result<usize> {
     let result = input
         .iter()
         .map(|s| s.unfold())
-        .map(|s| count_possible_solutions(s.springs.clone(), 0, s.config.to_vec()))
+        .map(|s| count_possible_solutions(&s.springs, &s.config))
         .sum::<usize>();
     Ok(result)
 }
 
+fn explain(input: &[SpringConfig], count: fn(&SpringConfig) -> usize) -> Explanation {
+    let counts = input.iter().map(count).collect::<Vec<_>>();
+    let rows = input
+        .iter()
+        .zip(counts.iter())
+        .map(|(s, c)| {
+            vec![
+                s.springs.iter().collect::<String>(),
+                s.config.iter().join(","),
+                c.to_string(),
+            ]
+        })
+        .collect();
+
+    Explanation::new(
+        &["springs", "groups", "arrangements"],
+        rows,
+        counts.iter().sum::<usize>(),
+    )
+}
+
+pub fn explain_part1(input: &[SpringConfig]) -> Result<Explanation> {
+    Ok(explain(input, |s| {
+        count_possible_solutions(&s.springs, &s.config)
+    }))
+}
+
+pub fn explain_part2(input: &[SpringConfig]) -> Result<Explanation> {
+    Ok(explain(input, |s| {
+        let s = s.unfold();
+        count_possible_solutions(&s.springs, &s.config)
+    }))
+}
+
+pub fn stream_part1<R: BufRead>(reader: R) -> Result<usize> {
+    input_reader(reader).try_fold(0, |acc, s| {
+        let s = s?;
+        Ok(acc + count_possible_solutions(&s.springs, &s.config))
+    })
+}
+
+pub fn stream_part2<R: BufRead>(reader: R) -> Result<usize> {
+    input_reader(reader).try_fold(0, |acc, s| {
+        let s = s?.unfold();
+        Ok(acc + count_possible_solutions(&s.springs, &s.config))
+    })
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -169,4 +215,20 @@ mod test {
         let data = input()?;
         Ok(assert_eq!(525152, solve_part2(&data)?))
     }
+
+    #[test]
+    fn part2_explain() -> Result<()> {
+        let data = input()?;
+        let explanation = explain_part2(&data)?;
+        assert_eq!("525152", explanation.answer);
+        Ok(assert_eq!(
+            vec!["?###????????", "3,2,1", "506250"],
+            explanation.rows[5]
+        ))
+    }
+
+    #[test]
+    fn part2_stream() -> Result<()> {
+        Ok(assert_eq!(525152, stream_part2(sample().as_bytes())?))
+    }
 }
@@ -2,6 +2,8 @@ use anyhow::{Context, Error, Result};
 use itertools::Itertools;
 use std::{collections::HashSet, str::FromStr};
 
+use crate::explain::Explanation;
+
 #[derive(Debug, Eq, PartialEq, PartialOrd, Ord, Hash, Clone)]
 pub enum Mirror {
     Horizontal(usize),
@@ -129,15 +131,21 @@ pub fn input_generator(input: &str) -> Result<Vec<Map>> {
         .context("Error while parsing input")
 }
 
+impl Mirror {
+    fn value(&self) -> usize {
+        match self {
+            Mirror::Horizontal(v) => v * 100,
+            Mirror::Vertical(v) => *v,
+        }
+    }
+}
+
 #[aoc(day13, part1)]
 pub fn solve_part1(input: &[Map]) -> Result<usize> {
     Ok(input
         .iter()
         .filter_map(|m| m.find_mirror(None))
-        .map(|m| match m {
-            Mirror::Horizontal(v) => v * 100,
-            Mirror::Vertical(v) => v,
-        })
+        .map(|m| m.value())
         .sum())
 }
 
@@ -146,13 +154,37 @@ pub fn solve_part2(input: &[Map]) -> Result<usize> {
     Ok(input
         .iter()
         .filter_map(|m| m.smudge())
-        .map(|m| match m {
-            Mirror::Horizontal(v) => v * 100,
-            Mirror::Vertical(v) => v,
-        })
+        .map(|m| m.value())
         .sum())
 }
 
+fn explain(mirrors: Vec<Option<Mirror>>) -> Explanation {
+    let rows = mirrors
+        .iter()
+        .enumerate()
+        .map(|(i, m)| {
+            let mirror = match m {
+                Some(Mirror::Horizontal(v)) => format!("after row {}", v),
+                Some(Mirror::Vertical(v)) => format!("after column {}", v),
+                None => "none".to_string(),
+            };
+            let value = m.as_ref().map(|m| m.value()).unwrap_or(0);
+            vec![(i + 1).to_string(), mirror, value.to_string()]
+        })
+        .collect();
+
+    let answer = mirrors.iter().flatten().map(|m| m.value()).sum::<usize>();
+    Explanation::new(&["pattern", "mirror", "value"], rows, answer)
+}
+
+pub fn explain_part1(input: &[Map]) -> Result<Explanation> {
+    Ok(explain(input.iter().map(|m| m.find_mirror(None)).collect()))
+}
+
+pub fn explain_part2(input: &[Map]) -> Result<Explanation> {
+    Ok(explain(input.iter().map(|m| m.smudge()).collect()))
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -190,4 +222,15 @@ mod test {
         let data = input()?;
         Ok(assert_eq!(400, solve_part2(&data)?))
     }
+
+    #[test]
+    fn part1_explain() -> Result<()> {
+        let data = input()?;
+        let explanation = explain_part1(&data)?;
+        assert_eq!("405", explanation.answer);
+        Ok(assert_eq!(
+            vec!["2", "after row 4", "400"],
+            explanation.rows[1]
+        ))
+    }
 }
@@ -1,6 +1,6 @@
 use anyhow::{Context, Result};
 
-use crate::utils::AocError::*;
+use crate::{explain::Explanation, simulation::Simulation, utils::AocError::*};
 
 #[derive(Debug, Clone, PartialEq, PartialOrd, Eq, Ord, Copy)]
 pub enum Tile {
@@ -20,6 +20,14 @@ impl Tile {
             _ => Err(GenericError).context("Unknown tile type")?,
         })
     }
+
+    fn to_char(self) -> char {
+        match self {
+            Tile::RoundRock => 'O',
+            Tile::CubeRock => '#',
+            Tile::Empty => '.',
+        }
+    }
 }
 
 #[aoc_generator(day14)]
@@ -150,6 +158,50 @@ fn cycle(dish: &[Vec<Tile>]) -> Vec<Vec<Tile>> {
     dish
 }
 
+/// Runs one spin cycle per step.
+#[derive(Debug, Clone)]
+pub struct SpinSimulation {
+    dish: Vec<Vec<Tile>>,
+    cycles: usize,
+}
+
+impl SpinSimulation {
+    pub fn new(dish: &[Vec<Tile>]) -> Self {
+        SpinSimulation {
+            dish: dish.to_vec(),
+            cycles: 0,
+        }
+    }
+}
+
+impl Simulation for SpinSimulation {
+    fn step(&mut self) -> Result<bool> {
+        let next = cycle(&self.dish);
+        if next == self.dish {
+            return Ok(false);
+        }
+
+        self.dish = next;
+        self.cycles += 1;
+        Ok(true)
+    }
+
+    fn render(&self) -> String {
+        let dish = self
+            .dish
+            .iter()
+            .map(|l| l.iter().map(|t| t.to_char()).collect::<String>())
+            .collect::<Vec<_>>()
+            .join("\n");
+        format!(
+            "cycles: {}, load: {}\n{}",
+            self.cycles,
+            eval(&self.dish),
+            dish
+        )
+    }
+}
+
 fn matches(haystack: &[usize], offset: usize, cycle_size: usize) -> bool {
     (0..cycle_size).all(|i| {
         haystack[offset + i] == haystack[offset + cycle_size + i]
@@ -157,8 +209,9 @@ fn matches(haystack: &[usize], offset: usize, cycle_size: usize) -> bool {
     })
 }
 
-#[aoc(day14, part2)]
-pub fn solve_part2(input: &[Vec<Tile>]) -> Result<usize> {
+/// The loads after every spin cycle together with the number of cycles
+/// before the loads repeat and the length of the repetition.
+fn spin_loads(input: &[Vec<Tile>]) -> Result<(Vec<usize>, usize, usize)> {
     let reps = 250;
     let mut evals = vec![];
     (0..reps).fold(input.to_vec(), |acc, _| {
@@ -181,21 +234,70 @@ pub fn solve_part2(input: &[Vec<Tile>]) -> Result<usize> {
     }
 
     match (offset, cycle_size) {
-        (Some(offset), Some(cycle_size)) => {
-            let rest = (1_000_000_000 - offset) % cycle_size;
-            let result = *evals
-                .iter()
-                .skip(offset)
-                .nth(rest)
-                .ok_or(GenericError)
-                .context("Could not find result")?;
-
-            Ok(result)
-        }
+        (Some(offset), Some(cycle_size)) => Ok((evals, offset, cycle_size)),
         _ => Err(GenericError).context("Could not find cycle")?,
     }
 }
 
+#[aoc(day14, part2)]
+pub fn solve_part2(input: &[Vec<Tile>]) -> Result<usize> {
+    let (evals, offset, cycle_size) = spin_loads(input)?;
+    let rest = (1_000_000_000 - offset) % cycle_size;
+    let result = *evals
+        .iter()
+        .skip(offset)
+        .nth(rest)
+        .ok_or(GenericError)
+        .context("Could not find result")?;
+
+    Ok(result)
+}
+
+pub fn explain_part1(input: &[Vec<Tile>]) -> Result<Explanation> {
+    let tilted = tilt_north(input);
+    let len = tilted.len();
+    let rows = tilted
+        .iter()
+        .enumerate()
+        .map(|(i, l)| {
+            let rocks = l.iter().filter(|r| **r == Tile::RoundRock).count();
+            vec![
+                (i + 1).to_string(),
+                rocks.to_string(),
+                (len - i).to_string(),
+                (rocks * (len - i)).to_string(),
+            ]
+        })
+        .collect();
+
+    Ok(Explanation::new(
+        &["row", "rocks", "weight", "load"],
+        rows,
+        eval(&tilted),
+    ))
+}
+
+pub fn explain_part2(input: &[Vec<Tile>]) -> Result<Explanation> {
+    let (evals, offset, cycle_size) = spin_loads(input)?;
+    let rows = evals[..offset + cycle_size]
+        .iter()
+        .enumerate()
+        .map(|(i, load)| {
+            let phase = match i.checked_sub(offset) {
+                Some(p) => format!("{}/{}", p, cycle_size),
+                None => "-".to_string(),
+            };
+            vec![i.to_string(), load.to_string(), phase]
+        })
+        .collect();
+
+    Ok(Explanation::new(
+        &["cycles", "load", "phase"],
+        rows,
+        solve_part2(input)?,
+    ))
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -228,4 +330,35 @@ O.#..O.#.#
         let data = input()?;
         Ok(assert_eq!(64, solve_part2(&data)?))
     }
+
+    #[test]
+    fn part1_explain() -> Result<()> {
+        let data = input()?;
+        let explanation = explain_part1(&data)?;
+        assert_eq!("136", explanation.answer);
+        Ok(assert_eq!(vec!["1", "5", "10", "50"], explanation.rows[0]))
+    }
+
+    #[test]
+    fn part2_explain() -> Result<()> {
+        let data = input()?;
+        let explanation = explain_part2(&data)?;
+        let phase = explanation
+            .rows
+            .iter()
+            .find(|r| r[2] != "-")
+            .ok_or(GenericError)?;
+        assert_eq!("64", explanation.answer);
+        Ok(assert_eq!("0/7", phase[2]))
+    }
+
+    #[test]
+    fn spin_simulation() -> Result<()> {
+        let data = input()?;
+        let mut sim = SpinSimulation::new(&data);
+        sim.step()?;
+        sim.step()?;
+        sim.step()?;
+        Ok(assert_eq!(69, eval(&sim.dish)))
+    }
 }
@@ -1,8 +1,11 @@
 use anyhow::{Context, Error, Result};
 use itertools::Itertools;
-use std::str::FromStr;
+use std::{
+    io::BufRead,
+    str::{self, FromStr},
+};
 
-use crate::utils::AocError::*;
+use crate::{explain::Explanation, utils::AocError::*};
 
 #[derive(Debug, Clone)]
 pub struct Lens {
@@ -79,6 +82,19 @@ pub fn input_generator(input: &str) -> Result<Vec<String>> {
         .collect_vec())
 }
 
+/// Reads the comma separated steps one at a time, so the input does not
+/// need to fit into memory as a single line.
+pub fn input_reader<R: BufRead>(reader: R) -> impl Iterator<Item = Result<String>> {
+    reader.split(b',').filter_map(|s| match s {
+        Ok(s) => match str::from_utf8(&s) {
+            Ok(s) if s.trim().is_empty() => None,
+            Ok(s) => Some(Ok(s.trim().to_string())),
+            Err(e) => Some(Err(e.into())),
+        },
+        Err(e) => Some(Err(e.into())),
+    })
+}
+
 #[aoc(day15, part1)]
 pub fn solve_part1(input: &[String]) -> Result<u32> {
     Ok(input.iter().map(|s| hash(s)).sum())
@@ -104,45 +120,108 @@ pub fn index(s: &str) -> Result<usize> {
     }
 }
 
+fn apply(boxes: &mut [Bin], lens: &str) -> Result<()> {
+    let index = index(lens)?;
+    let mut b = boxes[index].clone();
+
+    if lens.contains('=') {
+        let l = Lens::from_str(lens)?;
+        if let Some(pos) = b
+            .lenses
+            .clone()
+            .iter()
+            .find_position(|v| v.label == l.label)
+        {
+            b.lenses[pos.0].focal = l.focal;
+        } else {
+            b.lenses.push(l);
+        }
+    } else {
+        let label = lens
+            .split('-')
+            .next()
+            .ok_or(GenericError)
+            .context("No label")?;
+        if let Some(pos) = b.lenses.iter().find_position(|v| v.label == label) {
+            b.lenses.remove(pos.0);
+        }
+    }
+    boxes[index] = b;
+
+    Ok(())
+}
+
+fn focusing_power(boxes: &[Bin]) -> usize {
+    boxes
+        .iter()
+        .enumerate()
+        .map(|(i, b)| (i + 1) * b.power())
+        .sum()
+}
+
 #[aoc(day15, part2)]
 pub fn solve_part2(input: &[String]) -> Result<usize> {
     let mut boxes = vec![Bin::new(); 256];
     for lens in input {
-        let index = index(lens)?;
-        let mut b = boxes[index].clone();
-
-        if lens.contains('=') {
-            let l = Lens::from_str(lens)?;
-            if let Some(pos) = b
-                .lenses
-                .clone()
-                .iter()
-                .find_position(|v| v.label == l.label)
-            {
-                b.lenses[pos.0].focal = l.focal;
-            } else {
-                b.lenses.push(l);
-            }
-        } else {
-            let label = lens
-                .split('-')
-                .next()
-                .ok_or(GenericError)
-                .context("No label")?;
-            if let Some(pos) = b.lenses.iter().find_position(|v| v.label == label) {
-                b.lenses.remove(pos.0);
-            }
-        }
-        boxes[index] = b;
+        apply(&mut boxes, lens)?;
+    }
+
+    Ok(focusing_power(&boxes))
+}
+
+pub fn explain_part1(input: &[String]) -> Result<Explanation> {
+    let rows = input
+        .iter()
+        .map(|s| vec![s.clone(), hash(s).to_string()])
+        .collect();
+
+    Ok(Explanation::new(
+        &["step", "hash"],
+        rows,
+        solve_part1(input)?,
+    ))
+}
+
+pub fn explain_part2(input: &[String]) -> Result<Explanation> {
+    let mut boxes = vec![Bin::new(); 256];
+    for lens in input {
+        apply(&mut boxes, lens)?;
     }
 
-    let power = boxes
+    let rows = boxes
         .iter()
         .enumerate()
-        .map(|(i, b)| (i + 1) * b.power())
-        .sum();
+        .flat_map(|(i, b)| {
+            b.lenses.iter().enumerate().map(move |(slot, l)| {
+                vec![
+                    l.label.clone(),
+                    i.to_string(),
+                    (slot + 1).to_string(),
+                    l.focal.to_string(),
+                    ((i + 1) * (slot + 1) * l.focal as usize).to_string(),
+                ]
+            })
+        })
+        .collect();
+
+    Ok(Explanation::new(
+        &["lens", "box", "slot", "focal length", "power"],
+        rows,
+        focusing_power(&boxes),
+    ))
+}
 
-    Ok(power)
+pub fn stream_part1<R: BufRead>(reader: R) -> Result<u32> {
+    input_reader(reader).try_fold(0, |acc, s| Ok(acc + hash(&s?)))
+}
+
+pub fn stream_part2<R: BufRead>(reader: R) -> Result<usize> {
+    let mut boxes = vec![Bin::new(); 256];
+    for lens in input_reader(reader) {
+        apply(&mut boxes, &lens?)?;
+    }
+
+    Ok(focusing_power(&boxes))
 }
 
 #[cfg(test)]
@@ -168,4 +247,21 @@ mod test {
         let data = input()?;
         Ok(assert_eq!(145, solve_part2(&data)?))
     }
+
+    #[test]
+    fn part2_explain() -> Result<()> {
+        let data = input()?;
+        let explanation = explain_part2(&data)?;
+        assert_eq!("145", explanation.answer);
+        assert_eq!(vec!["cm", "0", "2", "2", "4"], explanation.rows[1]);
+        Ok(assert_eq!(
+            vec!["pc", "3", "3", "6", "72"],
+            explanation.rows[4]
+        ))
+    }
+
+    #[test]
+    fn part2_stream() -> Result<()> {
+        Ok(assert_eq!(145, stream_part2(sample().as_bytes())?))
+    }
 }
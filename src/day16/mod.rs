@@ -1,9 +1,10 @@
 use anyhow::{Context, Result};
 use itertools::Itertools;
+#[cfg(feature = "parallel")]
 use rayon::prelude::*;
 use std::{cmp::max, collections::HashSet};
 
-use crate::utils::AocError::*;
+use crate::{explain::Explanation, simulation::Simulation, utils::AocError::*};
 
 type Set<T> = HashSet<T>;
 pub type Coords = (isize, isize);
@@ -141,6 +142,100 @@ pub fn trace(
     }
 }
 
+/// Spreads all active beams by one tile per step.
+#[derive(Debug, Clone)]
+pub struct BeamSimulation {
+    map: Vec<Vec<char>>,
+    beams: Vec<Beam>,
+    energized: Set<Coords>,
+    visited: Set<(Coords, Direction)>,
+}
+
+impl BeamSimulation {
+    pub fn new(map: &[Vec<char>], start: Beam) -> Self {
+        let mut visited = Set::new();
+        visited.insert((start.pos, start.dir.clone()));
+
+        BeamSimulation {
+            map: map.to_vec(),
+            beams: vec![start],
+            energized: Set::new(),
+            visited,
+        }
+    }
+
+    pub fn energized(&self) -> usize {
+        self.energized.len()
+    }
+
+    fn outgoing(tile: char, dir: &Direction) -> Vec<Direction> {
+        use Direction::*;
+
+        match (tile, dir) {
+            ('-', Up | Down) => vec![Left, Right],
+            ('|', Left | Right) => vec![Up, Down],
+            ('\\', Up) | ('/', Down) => vec![Left],
+            ('\\', Right) | ('/', Left) => vec![Down],
+            ('\\', Down) | ('/', Up) => vec![Right],
+            ('\\', Left) | ('/', Right) => vec![Up],
+            (_, d) => vec![d.clone()],
+        }
+    }
+}
+
+impl Simulation for BeamSimulation {
+    fn step(&mut self) -> Result<bool> {
+        if self.beams.is_empty() {
+            return Ok(false);
+        }
+
+        let mut next = vec![];
+        for beam in &self.beams {
+            self.energized.insert(beam.pos);
+
+            let tile = self.map[beam.pos.1 as usize][beam.pos.0 as usize];
+            for dir in Self::outgoing(tile, &beam.dir) {
+                let n = Beam::new(beam.pos, dir).next();
+                if is_within(&self.map, &n.pos) && self.visited.insert((n.pos, n.dir.clone())) {
+                    next.push(n);
+                }
+            }
+        }
+
+        self.beams = next;
+        Ok(true)
+    }
+
+    fn render(&self) -> String {
+        let map = self
+            .map
+            .iter()
+            .enumerate()
+            .map(|(y, l)| {
+                l.iter()
+                    .enumerate()
+                    .map(|(x, c)| {
+                        let pos = (x as isize, y as isize);
+                        if self.beams.iter().any(|b| b.pos == pos) {
+                            '*'
+                        } else if *c == '.' && self.energized.contains(&pos) {
+                            '#'
+                        } else {
+                            *c
+                        }
+                    })
+                    .collect::<String>()
+            })
+            .join("\n");
+        format!(
+            "beams: {}, energized: {}\n{}",
+            self.beams.len(),
+            self.energized(),
+            map
+        )
+    }
+}
+
 #[aoc(day16, part1)]
 pub fn solve_part1(input: &[Vec<char>]) -> Result<usize> {
     let start = Beam::new((0, 0), Direction::Right);
@@ -158,7 +253,12 @@ pub fn solve_part2(input: &[Vec<char>]) -> Result<usize> {
     let width = input[0].len() as isize;
     let height = input.len() as isize;
 
-    let max_x = (0..width).into_par_iter()
+    let xs = 0..width;
+    let ys = 1..height - 1;
+    #[cfg(feature = "parallel")]
+    let (xs, ys) = (xs.into_par_iter(), ys.into_par_iter());
+
+    let max_x = xs
         .map(|x| {
             let start_top = Beam::new((x, 0), Direction::Down);
             let mut path_top = Set::new();
@@ -178,7 +278,7 @@ pub fn solve_part2(input: &[Vec<char>]) -> Result<usize> {
         .ok_or(GenericError)
         .context("Could not find max item in x dir")?;
 
-    let max_y = (1..height - 1).into_par_iter()
+    let max_y = ys
         .map(|y| {
             let start_left = Beam::new((0, y), Direction::Right);
             let mut path_left = Set::new();
@@ -201,6 +301,61 @@ pub fn solve_part2(input: &[Vec<char>]) -> Result<usize> {
     Ok(max(max_x, max_y))
 }
 
+/// The tiles energized by a beam entering the map at `start`.
+fn energize(map: &[Vec<char>], start: &Beam) -> Set<Coords> {
+    let mut path = Set::new();
+    let mut visited = Set::new();
+    trace(map, start, &mut path, &mut visited);
+    path
+}
+
+pub fn explain_part1(input: &[Vec<char>]) -> Result<Explanation> {
+    let path = energize(input, &Beam::new((0, 0), Direction::Right));
+    let rows = (0..input.len() as isize)
+        .map(|y| {
+            let count = path.iter().filter(|p| p.1 == y).count();
+            vec![(y + 1).to_string(), count.to_string()]
+        })
+        .collect();
+
+    Ok(Explanation::new(&["row", "energized"], rows, path.len()))
+}
+
+pub fn explain_part2(input: &[Vec<char>]) -> Result<Explanation> {
+    let width = input[0].len() as isize;
+    let height = input.len() as isize;
+
+    let vertical = (0..width).flat_map(|x| {
+        [
+            Beam::new((x, 0), Direction::Down),
+            Beam::new((x, height - 1), Direction::Up),
+        ]
+    });
+    let horizontal = (1..height - 1).flat_map(|y| {
+        [
+            Beam::new((0, y), Direction::Right),
+            Beam::new((width - 1, y), Direction::Left),
+        ]
+    });
+
+    let rows = vertical
+        .chain(horizontal)
+        .map(|b| {
+            vec![
+                format!("{},{}", b.pos.1 + 1, b.pos.0 + 1),
+                format!("{:?}", b.dir),
+                energize(input, &b).len().to_string(),
+            ]
+        })
+        .collect();
+
+    Ok(Explanation::new(
+        &["entry", "direction", "energized"],
+        rows,
+        solve_part2(input)?,
+    ))
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -233,4 +388,20 @@ mod test {
         let data = input()?;
         Ok(assert_eq!(51, solve_part2(&data)?))
     }
+
+    #[test]
+    fn part2_explain() -> Result<()> {
+        let data = input()?;
+        let explanation = explain_part2(&data)?;
+        assert_eq!("51", explanation.answer);
+        Ok(assert_eq!(vec!["1,4", "Down", "51"], explanation.rows[6]))
+    }
+
+    #[test]
+    fn beam_simulation() -> Result<()> {
+        let data = input()?;
+        let mut sim = BeamSimulation::new(&data, Beam::new((0, 0), Direction::Right));
+        while sim.step()? {}
+        Ok(assert_eq!(46, sim.energized()))
+    }
 }
@@ -2,7 +2,7 @@ use anyhow::Result;
 use itertools::Itertools;
 use pathfinding::prelude::dijkstra;
 
-use crate::utils::AocError::*;
+use crate::{explain::Explanation, utils::AocError::*};
 
 type Coords = (i32, i32);
 
@@ -78,14 +78,17 @@ fn successors(
     neighbors
 }
 
-fn trace(map: &[Vec<u32>], ultra: bool) -> Option<u32> {
+type Node = (Coords, Coords, i32);
+
+/// The nodes on the route with the least heat loss and the total loss.
+fn route(map: &[Vec<u32>], ultra: bool) -> Option<(Vec<Node>, u32)> {
     let height = map.len();
     let width = map[0].len();
     let min_straight = if ultra { 3 } else { 0 };
 
     let start = (0, 0);
     let dir = (1, 0);
-    let result = dijkstra(
+    dijkstra(
         &(start, dir, if ultra { -1 } else { 0 }),
         |node| successors(map, width, height, node, ultra),
         |pos| {
@@ -93,9 +96,11 @@ fn trace(map: &[Vec<u32>], ultra: bool) -> Option<u32> {
                 && pos.0 .0 as usize == width - 1
                 && pos.0 .1 as usize == height - 1
         },
-    )?;
+    )
+}
 
-    Some(result.1)
+fn trace(map: &[Vec<u32>], ultra: bool) -> Option<u32> {
+    Some(route(map, ultra)?.1)
 }
 
 #[aoc(day17, part1)]
@@ -108,6 +113,41 @@ pub fn solve_part2(input: &[Vec<u32>]) -> Result<u32> {
     Ok(trace(input, true).ok_or(GenericError)?)
 }
 
+fn explain(map: &[Vec<u32>], ultra: bool) -> Result<Explanation> {
+    let (nodes, loss) = route(map, ultra).ok_or(GenericError)?;
+
+    let mut total = 0;
+    let rows = nodes
+        .iter()
+        .skip(1)
+        .enumerate()
+        .map(|(step, (pos, _, _))| {
+            let block = map[pos.1 as usize][pos.0 as usize];
+            total += block;
+            vec![
+                (step + 1).to_string(),
+                format!("{},{}", pos.1 + 1, pos.0 + 1),
+                block.to_string(),
+                total.to_string(),
+            ]
+        })
+        .collect();
+
+    Ok(Explanation::new(
+        &["step", "block", "heat loss", "total"],
+        rows,
+        loss,
+    ))
+}
+
+pub fn explain_part1(input: &[Vec<u32>]) -> Result<Explanation> {
+    explain(input, false)
+}
+
+pub fn explain_part2(input: &[Vec<u32>]) -> Result<Explanation> {
+    explain(input, true)
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -138,6 +178,16 @@ mod test {
         Ok(assert_eq!(102, solve_part1(&data)?))
     }
 
+    #[test]
+    fn part1_explain() -> Result<()> {
+        let data = input(sample1())?;
+        let explanation = explain_part1(&data)?;
+        let last = explanation.rows.last().ok_or(GenericError)?;
+        assert_eq!("102", explanation.answer);
+        assert_eq!("13,13", last[1]);
+        Ok(assert_eq!("102", last[3]))
+    }
+
     fn sample2() -> &'static str {
         "111111111111
 999999999991
@@ -2,10 +2,14 @@ use anyhow::{Context, Error, Result};
 use itertools::{Itertools, MinMaxResult};
 use std::{
     collections::{HashMap, VecDeque},
+    io::BufRead,
     str::FromStr,
 };
 
-use crate::utils::AocError::*;
+use crate::{
+    explain::Explanation,
+    utils::{read_lines, AocError::*},
+};
 
 type Coords = (isize, isize);
 
@@ -117,6 +121,10 @@ pub fn input_generator(input: &str) -> Result<Vec<Instruction>> {
         .context("Error while parsing input")
 }
 
+pub fn input_reader<R: BufRead>(reader: R) -> impl Iterator<Item = Result<Instruction>> {
+    read_lines(reader, Instruction::from_str)
+}
+
 pub fn walk(
     map: &mut HashMap<Coords, Vec<String>>,
     start: &Coords,
@@ -315,6 +323,77 @@ pub fn solve_part2(input: &[Instruction]) -> Result<isize> {
     Ok(result + border_length / 2 + 1)
 }
 
+/// Same as `trace_corners` and `shoelace` combined, but only keeps the
+/// current corner around instead of all of them.
+fn lagoon_size<I>(instr: I) -> Result<isize>
+where
+    I: Iterator<Item = Result<Instruction>>,
+{
+    use Direction::*;
+
+    let mut pos: Coords = (0, 0);
+    let mut sum = 0;
+    let mut border = 0;
+
+    for i in instr {
+        let i = i?;
+        let len = i.len as isize;
+        let next = match i.dir {
+            Up => (pos.0, pos.1 - len),
+            Right => (pos.0 + len, pos.1),
+            Down => (pos.0, pos.1 + len),
+            Left => (pos.0 - len, pos.1),
+        };
+        sum += pos.0 * next.1 - pos.1 * next.0;
+        border += len;
+        pos = next;
+    }
+
+    Ok((sum / 2).abs() + border / 2 + 1)
+}
+
+fn explain(instr: &[Instruction]) -> Result<Explanation> {
+    let (corners, _) = trace_corners(instr, &(0, 0));
+    let rows = instr
+        .iter()
+        .zip(corners.windows(2))
+        .map(|(i, c)| {
+            vec![
+                format!("{:?}", i.dir),
+                i.len.to_string(),
+                format!("{},{}", c[1].1, c[1].0),
+                (c[0].0 * c[1].1 - c[0].1 * c[1].0).to_string(),
+            ]
+        })
+        .collect();
+
+    Ok(Explanation::new(
+        &["direction", "length", "corner", "area term"],
+        rows,
+        lagoon_size(instr.iter().cloned().map(Ok))?,
+    ))
+}
+
+pub fn explain_part1(input: &[Instruction]) -> Result<Explanation> {
+    explain(input)
+}
+
+pub fn explain_part2(input: &[Instruction]) -> Result<Explanation> {
+    let input = input
+        .iter()
+        .map(|i| i.part2())
+        .collect::<Result<Vec<_>>>()?;
+    explain(&input)
+}
+
+pub fn stream_part1<R: BufRead>(reader: R) -> Result<isize> {
+    lagoon_size(input_reader(reader))
+}
+
+pub fn stream_part2<R: BufRead>(reader: R) -> Result<isize> {
+    lagoon_size(input_reader(reader).map(|i| i?.part2()))
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -351,4 +430,25 @@ U 2 (#7a21e3)"
         let data = input()?;
         Ok(assert_eq!(952408144115, solve_part2(&data)?))
     }
+
+    #[test]
+    fn part1_stream() -> Result<()> {
+        Ok(assert_eq!(62, stream_part1(sample().as_bytes())?))
+    }
+
+    #[test]
+    fn part1_explain() -> Result<()> {
+        let data = input()?;
+        let explanation = explain_part1(&data)?;
+        assert_eq!("62", explanation.answer);
+        Ok(assert_eq!(
+            vec!["Down", "5", "5,6", "30"],
+            explanation.rows[1]
+        ))
+    }
+
+    #[test]
+    fn part2_stream() -> Result<()> {
+        Ok(assert_eq!(952408144115, stream_part2(sample().as_bytes())?))
+    }
 }
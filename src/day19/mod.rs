@@ -1,7 +1,7 @@
 use anyhow::{Context, Error, Result};
 use std::{collections::HashMap, str::FromStr};
 
-use crate::utils::AocError::*;
+use crate::{explain::Explanation, utils::AocError::*};
 
 #[derive(Debug)]
 pub struct Part {
@@ -349,45 +349,38 @@ fn prod(ranges: &[Rang<i32>]) -> u128 {
     ranges.iter().map(|r| (r.end - r.start + 1) as u128).product()
 }
 
-pub fn trace(workflows: &HashMap<String, Workflow>, workflow: &Workflow, ranges: &[Rang<i32>]) -> u128 {
-    let mut sum = 0;
-    let mut ranges = ranges.to_vec().clone();
+/// The ranges of ratings that end up accepted when starting at `workflow`.
+pub fn trace(
+    workflows: &HashMap<String, Workflow>,
+    workflow: &Workflow,
+    ranges: &[Rang<i32>],
+) -> Result<Vec<Vec<Rang<i32>>>> {
+    let mut accepted = vec![];
+    let mut ranges = ranges.to_vec();
 
     for rule in &workflow.rules {
         let (split, next) = rule.split(&ranges);
-
-        if let Some(split) = split {
-            if *next == Next::Accept {
-                sum += prod(&split.0);
-            }
-
-            if *next == Next::Reject {
-                sum += 0;
-            }
-
-            if let Next::Workflow(next) = next {
-                let next_wf = workflows.get(next).unwrap();
-                sum += trace(workflows, next_wf, &split.0);
-            }
-
-            ranges = split.1;
-        } else {
-            if *next == Next::Accept {
-                sum += prod(&ranges);
-            }
-
-            if *next == Next::Reject {
-                sum += 0;
-            }
-
-            if let Next::Workflow(next) = next {
-                let next_wf = workflows.get(next).unwrap();
-                sum += trace(workflows, next_wf, &ranges);
+        let (matched, rest) = match split {
+            Some((valid, invalid)) => (valid, invalid),
+            None => (ranges.clone(), ranges.clone()),
+        };
+
+        match next {
+            Next::Accept => accepted.push(matched),
+            Next::Reject => {}
+            Next::Workflow(next) => {
+                let next_wf = workflows
+                    .get(next)
+                    .ok_or(GenericError)
+                    .with_context(|| format!("No workflow named {}", next))?;
+                accepted.extend(trace(workflows, next_wf, &matched)?);
             }
         }
+
+        ranges = rest;
     }
 
-    sum
+    Ok(accepted)
 }
 
 #[aoc(day19, part2)]
@@ -401,7 +394,68 @@ pub fn solve_part2(input: &(Vec<Workflow>, Vec<Part>)) -> Result<u128> {
 
     let start = workflows.get("in").context("No workflow named in")?;
     let ranges= vec![Rang::new(1, 4000), Rang::new(1, 4000), Rang::new(1, 4000), Rang::new(1, 4000)];
-    Ok(trace(&workflows, start, &ranges))
+    Ok(trace(&workflows, start, &ranges)?
+        .iter()
+        .map(|r| prod(r))
+        .sum())
+}
+
+pub fn explain_part1(input: &(Vec<Workflow>, Vec<Part>)) -> Result<Explanation> {
+    let (workflows, parts) = input;
+    let workflows = workflows
+        .iter()
+        .cloned()
+        .map(|w| (w.id.clone(), w))
+        .collect::<HashMap<String, Workflow>>();
+
+    let rows = parts
+        .iter()
+        .map(|p| {
+            let accepted = p.is_accepted(&workflows);
+            vec![
+                p.x.to_string(),
+                p.m.to_string(),
+                p.a.to_string(),
+                p.s.to_string(),
+                if accepted { "accepted" } else { "rejected" }.to_string(),
+                if accepted { p.rating() } else { 0 }.to_string(),
+            ]
+        })
+        .collect();
+
+    Ok(Explanation::new(
+        &["x", "m", "a", "s", "result", "rating"],
+        rows,
+        solve_part1(input)?,
+    ))
+}
+
+pub fn explain_part2(input: &(Vec<Workflow>, Vec<Part>)) -> Result<Explanation> {
+    let (workflows, _) = input;
+    let workflows = workflows
+        .iter()
+        .cloned()
+        .map(|w| (w.id.clone(), w))
+        .collect::<HashMap<String, Workflow>>();
+
+    let start = workflows.get("in").context("No workflow named in")?;
+    let ranges = vec![Rang::new(1, 4000); 4];
+    let accepted = trace(&workflows, start, &ranges)?;
+    let rows = accepted
+        .iter()
+        .map(|r| {
+            r.iter()
+                .map(|r| format!("{}-{}", r.start, r.end))
+                .chain(Some(prod(r).to_string()))
+                .collect()
+        })
+        .collect();
+
+    Ok(Explanation::new(
+        &["x", "m", "a", "s", "combinations"],
+        rows,
+        accepted.iter().map(|r| prod(r)).sum::<u128>(),
+    ))
 }
 
 #[cfg(test)]
@@ -443,4 +497,28 @@ hdj{m>838:A,pv}
         let data = input()?;
         Ok(assert_eq!(167409079868000, solve_part2(&data)?))
     }
+
+    #[test]
+    fn part2_explain() -> Result<()> {
+        let data = input()?;
+        let explanation = explain_part2(&data)?;
+        let total = explanation
+            .rows
+            .iter()
+            .map(|r| r[4].parse::<u128>())
+            .sum::<Result<u128, _>>()?;
+        assert_eq!("167409079868000", explanation.answer);
+        assert_eq!(167409079868000, total);
+        Ok(assert_eq!(
+            vec!["1-1415", "1-4000", "1-2005", "1-1350", "15320205000000"],
+            explanation.rows[0]
+        ))
+    }
+
+    #[test]
+    fn unknown_workflow() -> Result<()> {
+        let data = input_generator("in{s<1351:px,R}\n\n{x=1,m=1,a=1,s=1}")?;
+        assert!(solve_part2(&data).is_err());
+        Ok(assert!(explain_part2(&data).is_err()))
+    }
 }
@@ -4,6 +4,8 @@ use std::{
     str::FromStr,
 };
 
+use crate::{explain::Explanation, simulation::Simulation};
+
 #[derive(Debug, Clone, PartialEq, PartialOrd, Eq, Ord)]
 pub enum Signal {
     Low,
@@ -279,6 +281,59 @@ fn press_button(
     Ok((count, done))
 }
 
+/// Presses the button once per step and keeps track of the pulses sent.
+#[derive(Debug, Clone)]
+pub struct PulseSimulation {
+    machine: Machine,
+    presses: usize,
+    pulses: (usize, usize),
+}
+
+impl PulseSimulation {
+    pub fn new(machine: &Machine) -> Self {
+        PulseSimulation {
+            machine: machine.clone(),
+            presses: 0,
+            pulses: (0, 0),
+        }
+    }
+}
+
+impl Simulation for PulseSimulation {
+    fn step(&mut self) -> Result<bool> {
+        let (add, _) = press_button(&mut self.machine, None)?;
+        self.presses += 1;
+        self.pulses.0 += add.0;
+        self.pulses.1 += add.1;
+        Ok(true)
+    }
+
+    fn render(&self) -> String {
+        let flipflops = self
+            .machine
+            .flipflops
+            .iter()
+            .map(|f| format!("%{}: {}", f.name, if f.on { "on" } else { "off" }));
+        let conjunctions = self.machine.conjunctions.iter().map(|c| {
+            let mut input = c
+                .input
+                .iter()
+                .map(|(k, v)| format!("{}={:?}", k, v))
+                .collect::<Vec<_>>();
+            input.sort();
+            format!("&{}: {}", c.name, input.join(", "))
+        });
+
+        let mut lines = vec![format!(
+            "presses: {}, low: {}, high: {}",
+            self.presses, self.pulses.0, self.pulses.1
+        )];
+        lines.extend(flipflops);
+        lines.extend(conjunctions);
+        lines.join("\n")
+    }
+}
+
 #[aoc(day20, part1)]
 pub fn solve_part1(input: &Machine) -> Result<usize> {
     let mut machine = input.clone();
@@ -317,24 +372,62 @@ fn find_cycle(machine: &mut Machine, node: String, signal: Signal) -> Option<usi
     None
 }
 
-#[aoc(day20, part2)]
-pub fn solve_part2(input: &Machine) -> Result<usize> {
-    let modules = ["jm", "rh", "jg", "hf"]
+/// The number of presses after which each of the modules feeding the final
+/// conjunction sends a high pulse again.
+fn module_cycles(input: &Machine) -> Result<Vec<(&'static str, usize)>> {
+    ["jm", "rh", "jg", "hf"]
         .iter()
         .map(|m| {
             let mut machine = input.clone();
-            find_cycle(&mut machine, m.to_string(), Signal::High)
-                .with_context(|| format!("Could not find cycle for {}", m))
+            let cycle = find_cycle(&mut machine, m.to_string(), Signal::High)
+                .with_context(|| format!("Could not find cycle for {}", m))?;
+            Ok((*m, cycle))
         })
-        .collect::<Result<Vec<_>>>()?;
+        .collect()
+}
 
-    let count = modules
+#[aoc(day20, part2)]
+pub fn solve_part2(input: &Machine) -> Result<usize> {
+    let count = module_cycles(input)?
         .iter()
-        .fold(1, |acc, el| num::Integer::lcm(&acc, el));
+        .fold(1, |acc, (_, el)| num::Integer::lcm(&acc, el));
 
     Ok(count)
 }
 
+pub fn explain_part1(input: &Machine) -> Result<Explanation> {
+    let mut machine = input.clone();
+
+    let rows = (1..=1_000)
+        .map(|press| {
+            let ((low, high), _) = press_button(&mut machine, None)?;
+            Ok(vec![press.to_string(), low.to_string(), high.to_string()])
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(Explanation::new(
+        &["press", "low", "high"],
+        rows,
+        solve_part1(input)?,
+    ))
+}
+
+pub fn explain_part2(input: &Machine) -> Result<Explanation> {
+    let cycles = module_cycles(input)?;
+    let rows = cycles
+        .iter()
+        .map(|(m, c)| vec![m.to_string(), c.to_string()])
+        .collect();
+
+    Ok(Explanation::new(
+        &["module", "cycle"],
+        rows,
+        cycles
+            .iter()
+            .fold(1, |acc, (_, el)| num::Integer::lcm(&acc, el)),
+    ))
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -365,9 +458,28 @@ mod test {
         Ok(assert_eq!(32_000_000, solve_part1(&data)?))
     }
 
+    #[test]
+    fn part1_explain() -> Result<()> {
+        let data = input(sample1())?;
+        let explanation = explain_part1(&data)?;
+        assert_eq!("32000000", explanation.answer);
+        assert_eq!(1_000, explanation.rows.len());
+        Ok(assert_eq!(vec!["1", "8", "4"], explanation.rows[0]))
+    }
+
     #[test]
     fn part1_sample2() -> Result<()> {
         let data = input(sample2())?;
         Ok(assert_eq!(11_687_500, solve_part1(&data)?))
     }
+
+    #[test]
+    fn pulse_simulation() -> Result<()> {
+        let data = input(sample2())?;
+        let mut sim = PulseSimulation::new(&data);
+        for _ in 0..1_000 {
+            sim.step()?;
+        }
+        Ok(assert_eq!(11_687_500, sim.pulses.0 * sim.pulses.1))
+    }
 }
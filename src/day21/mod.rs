@@ -2,7 +2,7 @@ use anyhow::{Context, Result};
 use itertools::{Itertools, MinMaxResult};
 use std::collections::{HashMap, HashSet};
 
-use crate::utils::AocError::*;
+use crate::{explain::Explanation, simulation::Simulation, utils::AocError::*};
 
 #[derive(Debug, Clone, PartialEq, PartialOrd, Eq, Ord)]
 pub enum Tile {
@@ -81,6 +81,88 @@ fn can_reach(map: &Map, pos: &Vec<Coords>, size: &Option<Coords>) -> Vec<Coords>
     result.into_iter().collect_vec()
 }
 
+/// Expands the set of reachable garden plots by one step at a time.
+#[derive(Debug, Clone)]
+pub struct GardenSimulation {
+    map: Map,
+    size: Coords,
+    infinite: bool,
+    positions: Vec<Coords>,
+    steps: usize,
+}
+
+impl GardenSimulation {
+    pub fn new(map: &Map, infinite: bool) -> Result<Self> {
+        let start = map
+            .iter()
+            .find(|(_, v)| **v == Tile::Start)
+            .map(|(k, _)| *k)
+            .context("Could not find start")?;
+
+        Ok(GardenSimulation {
+            map: map.clone(),
+            size: size(map)?,
+            infinite,
+            positions: vec![start],
+            steps: 0,
+        })
+    }
+
+    pub fn reachable(&self) -> usize {
+        self.positions.len()
+    }
+}
+
+impl Simulation for GardenSimulation {
+    fn step(&mut self) -> Result<bool> {
+        let size = if self.infinite { Some(self.size) } else { None };
+        self.positions = can_reach(&self.map, &self.positions, &size);
+        self.steps += 1;
+        Ok(true)
+    }
+
+    fn render(&self) -> String {
+        let reached = self.positions.iter().collect::<HashSet<_>>();
+        let (min_x, max_x, min_y, max_y) = self.positions.iter().fold(
+            (0, self.size.0 - 1, 0, self.size.1 - 1),
+            |(min_x, max_x, min_y, max_y), p| {
+                (
+                    min_x.min(p.0),
+                    max_x.max(p.0),
+                    min_y.min(p.1),
+                    max_y.max(p.1),
+                )
+            },
+        );
+
+        let garden = (min_y..=max_y)
+            .map(|y| {
+                (min_x..=max_x)
+                    .map(|x| {
+                        let tile = (x.rem_euclid(self.size.0), y.rem_euclid(self.size.1));
+                        if reached.contains(&(x, y)) {
+                            'O'
+                        } else {
+                            match self.map.get(&tile) {
+                                Some(Tile::Wall) => '#',
+                                Some(Tile::Start) if tile == (x, y) => 'S',
+                                _ => '.',
+                            }
+                        }
+                    })
+                    .collect::<String>()
+            })
+            .join("\n");
+
+        format!(
+            "steps: {}, reachable: {}\n{}",
+            self.steps,
+            self.reachable(),
+            garden
+        )
+    }
+}
+
 #[aoc(day21, part1)]
 pub fn solve_part1(input: &Map) -> Result<usize> {
     let goal = 64;
@@ -119,27 +201,41 @@ fn diff_at(steps: usize, start: usize, cycle: usize, diffs: &[usize], offsets: &
     (observe / cycle) * diffs[(steps - start) % cycle] + offsets[(steps - start) % cycle]
 }
 
-#[aoc(day21, part2)]
-pub fn solve_part2(input: &Map) -> Result<usize> {
-    let goal = 26501365;
+/// The number of reachable plots after every step up to `steps`.
+fn reachable_per_step(input: &Map, steps: usize, size: &Option<Coords>) -> Result<Vec<usize>> {
     let start = input
         .iter()
         .find(|(_, v)| **v == Tile::Start)
         .map(|(k, _)| *k)
         .context("Could not find start")?;
 
-    let size = Some(size(input)?);
-
     let mut result = vec![start];
-    let mut last = 0;
-    let mut diffs = vec![];
-    let mut results = vec![];
-    for _ in 0..460 {
-        result = can_reach(input, &result, &size);
-        diffs.push(result.len() - last);
-        last = result.len();
-        results.push(result.len());
-    }
+    let counts = (0..steps)
+        .map(|_| {
+            result = can_reach(input, &result, size);
+            result.len()
+        })
+        .collect();
+
+    Ok(counts)
+}
+
+/// The number of steps to simulate before the growth of part 2 repeats
+/// often enough to be extrapolated.
+const OBSERVED_STEPS: usize = 460;
+
+/// The number of reachable plots after the goal number of steps, continuing
+/// the growth observed in `counts`.
+fn extrapolate(counts: &[usize]) -> usize {
+    let goal = 26501365;
+    let diffs = counts
+        .iter()
+        .scan(0, |last, c| {
+            let diff = c - *last;
+            *last = *c;
+            Some(diff)
+        })
+        .collect_vec();
 
     // these parameters are specific to my personal input and
     // most likely won't work with other people's input.
@@ -154,12 +250,43 @@ pub fn solve_part2(input: &Map) -> Result<usize> {
         .collect_vec();
     let offsets = diffs.iter().skip(start).take(cycle).cloned().collect_vec();
 
-    let mut result = results[start - 1];
+    let mut result = counts[start - 1];
     for i in start..goal {
         result += diff_at(i, start, cycle, &diff, &offsets)
     }
 
-    Ok(result)
+    result
+}
+
+#[aoc(day21, part2)]
+pub fn solve_part2(input: &Map) -> Result<usize> {
+    let counts = reachable_per_step(input, OBSERVED_STEPS, &Some(size(input)?))?;
+    Ok(extrapolate(&counts))
+}
+
+fn explain(counts: &[usize], answer: usize) -> Explanation {
+    let rows = counts
+        .iter()
+        .enumerate()
+        .map(|(i, c)| {
+            let previous = if i == 0 { 1 } else { counts[i - 1] };
+            let growth = *c as isize - previous as isize;
+            vec![(i + 1).to_string(), c.to_string(), growth.to_string()]
+        })
+        .collect();
+
+    Explanation::new(&["steps", "reachable", "growth"], rows, answer)
+}
+
+pub fn explain_part1(input: &Map) -> Result<Explanation> {
+    let counts = reachable_per_step(input, 64, &None)?;
+    Ok(explain(&counts, solve_part1(input)?))
+}
+
+/// The steps that the extrapolation of part 2 is based on.
+pub fn explain_part2(input: &Map) -> Result<Explanation> {
+    let counts = reachable_per_step(input, OBSERVED_STEPS, &Some(size(input)?))?;
+    Ok(explain(&counts, extrapolate(&counts)))
 }
 
 #[cfg(test)]
@@ -190,6 +317,25 @@ mod test {
         Ok(assert_eq!(42, solve_part1(&data)?))
     }
 
+    #[test]
+    fn part1_explain() -> Result<()> {
+        let data = input()?;
+        let explanation = explain_part1(&data)?;
+        assert_eq!("42", explanation.answer);
+        assert_eq!(vec!["1", "2", "1"], explanation.rows[0]);
+        Ok(assert_eq!(vec!["6", "16", "3"], explanation.rows[5]))
+    }
+
+    #[test]
+    fn garden_simulation() -> Result<()> {
+        let data = input()?;
+        let mut sim = GardenSimulation::new(&data, true)?;
+        for _ in 0..10 {
+            sim.step()?;
+        }
+        Ok(assert_eq!(50, sim.reachable()))
+    }
+
     // skip this one as it takes a few seconds
     // #[test]
     // fn part2_sample() -> Result<()> {
@@ -1,8 +1,12 @@
 use anyhow::{Context, Error, Result};
 use itertools::Itertools;
-use std::str::FromStr;
+use std::{io::BufRead, str::FromStr};
 
-use crate::utils::AocError::*;
+use crate::{
+    explain::Explanation,
+    simulation::Simulation,
+    utils::{read_lines, AocError::*},
+};
 
 type Coords = (isize, isize, isize);
 
@@ -51,6 +55,10 @@ pub fn input_generator(input: &str) -> Result<Vec<Brick>> {
         .context("Error while parsing input")
 }
 
+pub fn input_reader<R: BufRead>(reader: R) -> impl Iterator<Item = Result<Brick>> {
+    read_lines(reader, Brick::from_str)
+}
+
 impl Brick {
     fn new(from: Coords, to: Coords) -> Self {
         Self { from, to }
@@ -145,6 +153,66 @@ fn stabilize(bricks: &[Brick]) -> (Vec<Brick>, usize) {
     (bricks, count)
 }
 
+/// Lets every brick that is not supported drop by one unit per step.
+#[derive(Debug, Clone)]
+pub struct FallSimulation {
+    bricks: Vec<Brick>,
+    steps: usize,
+}
+
+impl FallSimulation {
+    pub fn new(bricks: &[Brick]) -> Self {
+        FallSimulation {
+            bricks: bricks.to_vec(),
+            steps: 0,
+        }
+    }
+}
+
+impl Simulation for FallSimulation {
+    fn step(&mut self) -> Result<bool> {
+        let mut has_fallen = false;
+        for i in 0..self.bricks.len() {
+            if can_fall(i, &self.bricks, None) {
+                self.bricks[i].fall();
+                has_fallen = true;
+            }
+        }
+
+        if has_fallen {
+            self.steps += 1;
+        }
+        Ok(has_fallen)
+    }
+
+    fn render(&self) -> String {
+        // Side view looking along the y axis, like in the puzzle description.
+        let max_x = self.bricks.iter().map(|b| b.to.0).max().unwrap_or(0);
+        let max_z = self.bricks.iter().map(|b| b.to.2).max().unwrap_or(0);
+
+        let mut lines = vec![format!("steps: {}", self.steps)];
+        for z in (1..=max_z).rev() {
+            let line = (0..=max_x)
+                .map(|x| {
+                    let hit = self
+                        .bricks
+                        .iter()
+                        .any(|b| b.from.0 <= x && x <= b.to.0 && b.from.2 <= z && z <= b.to.2);
+                    if hit {
+                        '#'
+                    } else {
+                        '.'
+                    }
+                })
+                .collect::<String>();
+            lines.push(format!("{} {}", line, z));
+        }
+        lines.push(format!("{} 0", "-".repeat(max_x as usize + 1)));
+
+        lines.join("\n")
+    }
+}
+
 #[aoc(day22, part1)]
 pub fn solve_part1(input: &[Brick]) -> Result<u32> {
     let bricks = stabilize(input).0;
@@ -186,6 +254,65 @@ pub fn solve_part2(input: &[Brick]) -> Result<usize> {
     Ok(count)
 }
 
+fn label(brick: &Brick) -> String {
+    let (from, to) = (brick.from, brick.to);
+    format!(
+        "{},{},{}~{},{},{}",
+        from.0, from.1, from.2, to.0, to.1, to.2
+    )
+}
+
+pub fn explain_part1(input: &[Brick]) -> Result<Explanation> {
+    let bricks = stabilize(input).0;
+    let rows = (0..bricks.len())
+        .map(|i| {
+            let safe = bricks
+                .iter()
+                .enumerate()
+                .all(|(idx, _)| !can_fall(idx, &bricks, Some(i)));
+            vec![
+                (i + 1).to_string(),
+                label(&bricks[i]),
+                if safe { "yes" } else { "no" }.to_string(),
+            ]
+        })
+        .collect();
+
+    Ok(Explanation::new(
+        &["brick", "settled at", "safe"],
+        rows,
+        solve_part1(input)?,
+    ))
+}
+
+pub fn explain_part2(input: &[Brick]) -> Result<Explanation> {
+    let bricks = stabilize(input).0;
+    let falling = (0..bricks.len())
+        .map(|i| {
+            let without_i = bricks
+                .iter()
+                .enumerate()
+                .filter(|(idx, _)| *idx != i)
+                .map(|(_, b)| b)
+                .cloned()
+                .collect_vec();
+            stabilize(&without_i).1
+        })
+        .collect_vec();
+
+    let rows = falling
+        .iter()
+        .enumerate()
+        .map(|(i, f)| vec![(i + 1).to_string(), label(&bricks[i]), f.to_string()])
+        .collect();
+
+    Ok(Explanation::new(
+        &["brick", "settled at", "falling"],
+        rows,
+        falling.iter().sum::<usize>(),
+    ))
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -223,4 +350,29 @@ mod test {
         let data = input()?;
         Ok(assert_eq!(7, solve_part2(&data)?))
     }
+
+    #[test]
+    fn part2_explain() -> Result<()> {
+        let data = input()?;
+        let explanation = explain_part2(&data)?;
+        assert_eq!("7", explanation.answer);
+        Ok(assert_eq!(
+            vec!["1", "1,0,1~1,2,1", "6"],
+            explanation.rows[0]
+        ))
+    }
+
+    #[test]
+    fn fall_simulation() -> Result<()> {
+        let data = input()?;
+        let mut sim = FallSimulation::new(&data);
+        while sim.step()? {}
+
+        let expected = stabilize(&data).0;
+        Ok(assert!(sim
+            .bricks
+            .iter()
+            .zip(expected.iter())
+            .all(|(a, b)| a.from == b.from && a.to == b.to)))
+    }
 }
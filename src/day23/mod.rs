@@ -1,6 +1,8 @@
 use anyhow::{Context, Result};
 use itertools::Itertools;
 
+use crate::explain::Explanation;
+
 type Coords = (usize, usize);
 
 #[aoc_generator(day23)]
@@ -86,63 +88,120 @@ fn successors(
         .collect_vec()
 }
 
+/// The longest path from `p` to `end`, listed backwards from `end` to `p`.
 fn find_longest_path(
     map: &[Vec<char>],
     visited: &mut Vec<Vec<bool>>,
     size: (usize, usize),
     p: &Coords,
     end: &Coords,
-    len: usize,
     ignore_slopes: bool,
-) -> Option<usize> {
+) -> Option<Vec<Coords>> {
     if p == end {
-        return Some(len);
+        return Some(vec![*p]);
     }
 
     let (width, height) = size;
     let next = successors(map, visited, width, height, p, ignore_slopes);
 
-    next.into_iter()
-        .map(|n| {
+    let mut path = next
+        .into_iter()
+        .filter_map(|n| {
             visited[n.1][n.0] = true;
-            let result =
-                find_longest_path(map, visited, size, &n, end, len + 1, ignore_slopes);
+            let result = find_longest_path(map, visited, size, &n, end, ignore_slopes);
             visited[n.1][n.0] = false;
             result
         })
-        .max()?
+        .max_by_key(|path| path.len())?;
+    path.push(*p);
+    Some(path)
 }
 
-#[aoc(day23, part1)]
-pub fn solve_part1(input: &[Vec<char>]) -> Result<usize> {
-    let width = input[0].len();
-    let height = input.len();
+/// The tiles of the longest hike from the start to the end.
+fn longest_hike(map: &[Vec<char>], ignore_slopes: bool) -> Result<Vec<Coords>> {
+    let width = map[0].len();
+    let height = map.len();
 
-    let start = find_start(input).context("Could not find start")?;
-    let end = find_end(input).context("Could not find end")?;
+    let start = find_start(map).context("Could not find start")?;
+    let end = find_end(map).context("Could not find end")?;
     let mut visited = vec![vec![false; width]; height];
     visited[start.1][start.0] = true;
 
-    let result = find_longest_path(input, &mut visited, (width, height), &start, &end, 0, false)
-        .context("Could not find longest path")?;
+    let mut path = find_longest_path(
+        map,
+        &mut visited,
+        (width, height),
+        &start,
+        &end,
+        ignore_slopes,
+    )
+    .context("Could not find longest path")?;
+    path.reverse();
+
+    Ok(path)
+}
 
-    Ok(result)
+#[aoc(day23, part1)]
+pub fn solve_part1(input: &[Vec<char>]) -> Result<usize> {
+    Ok(longest_hike(input, false)?.len() - 1)
 }
 
 #[aoc(day23, part2)]
 pub fn solve_part2(input: &[Vec<char>]) -> Result<usize> {
-    let width = input[0].len();
-    let height = input.len();
+    Ok(longest_hike(input, true)?.len() - 1)
+}
 
-    let start = find_start(input).context("Could not find start")?;
-    let end = find_end(input).context("Could not find end")?;
-    let mut visited = vec![vec![false; width]; height];
-    visited[start.1][start.0] = true;
+fn is_junction(map: &[Vec<char>], p: &Coords) -> bool {
+    let neighbors = [(1, 0), (0, 1), (-1, 0), (0, -1)]
+        .iter()
+        .filter(|(dx, dy)| {
+            let x = p.0 as isize + dx;
+            let y = p.1 as isize + dy;
+            x >= 0
+                && y >= 0
+                && map
+                    .get(y as usize)
+                    .and_then(|l| l.get(x as usize))
+                    .is_some_and(|c| *c != '#')
+        })
+        .count();
+    neighbors > 2
+}
 
-    let result = find_longest_path(input, &mut visited, (width, height), &start, &end, 0, true)
-        .context("Could not find longest path")?;
+fn explain(map: &[Vec<char>], ignore_slopes: bool) -> Result<Explanation> {
+    let best = longest_hike(map, ignore_slopes)?;
+    let end = find_end(map).context("Could not find end")?;
+    let longest = best.len() - 1;
 
-    Ok(result)
+    let mut previous = 0;
+    let rows = best
+        .iter()
+        .enumerate()
+        .filter(|(_, p)| is_junction(map, p) || *p == &end)
+        .map(|(steps, p)| {
+            let segment = steps - previous;
+            previous = steps;
+            vec![
+                format!("{},{}", p.1 + 1, p.0 + 1),
+                steps.to_string(),
+                segment.to_string(),
+            ]
+        })
+        .collect();
+
+    Ok(Explanation::new(
+        &["junction", "steps", "segment"],
+        rows,
+        longest,
+    ))
+}
+
+pub fn explain_part1(input: &[Vec<char>]) -> Result<Explanation> {
+    explain(input, false)
+}
+
+pub fn explain_part2(input: &[Vec<char>]) -> Result<Explanation> {
+    explain(input, true)
 }
 
 #[cfg(test)]
@@ -185,6 +244,15 @@ mod test {
         Ok(assert_eq!(94, solve_part1(&data)?))
     }
 
+    #[test]
+    fn part1_explain() -> Result<()> {
+        let data = input()?;
+        let explanation = explain_part1(&data)?;
+        let last = explanation.rows.last().context("No junctions")?;
+        assert_eq!("94", explanation.answer);
+        Ok(assert_eq!(["23,22", "94"], last[..2]))
+    }
+
     #[test]
     fn part2_sample() -> Result<()> {
         let data = input()?;
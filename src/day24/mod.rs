@@ -1,6 +1,8 @@
 use anyhow::{Context, Error, Result};
 use gauss_jordan_elimination::gauss_jordan_elimination_generic;
-use std::str::FromStr;
+use std::{fmt, io::BufRead, str::FromStr};
+
+use crate::{explain::Explanation, utils::read_lines};
 
 type Coords = (i128, i128, i128);
 type Coordsf = (f64, f64, f64);
@@ -115,26 +117,70 @@ pub fn input_generator(input: &str) -> Result<Vec<Stone>> {
         .context("Error while parsing input")
 }
 
+pub fn input_reader<R: BufRead>(reader: R) -> impl Iterator<Item = Result<Stone>> {
+    read_lines(reader, Stone::from_str)
+}
+
+fn test_area(input: &[Stone]) -> (f64, f64) {
+    if input.len() == 5 {
+        (7.0, 27.0)
+    } else {
+        (200000000000000.0, 400000000000000.0)
+    }
+}
+
+/// How the paths of two hailstones relate to the test area.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum Crossing {
+    Inside,
+    Outside,
+    /// The paths crossed in the past of stone A, stone B or both.
+    Past {
+        a: bool,
+        b: bool,
+    },
+    Parallel,
+}
+
+impl fmt::Display for Crossing {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Crossing::Inside => write!(f, "inside"),
+            Crossing::Outside => write!(f, "outside"),
+            Crossing::Past { a: true, b: true } => write!(f, "past for both"),
+            Crossing::Past { a: true, .. } => write!(f, "past for A"),
+            Crossing::Past { .. } => write!(f, "past for B"),
+            Crossing::Parallel => write!(f, "parallel"),
+        }
+    }
+}
+
+/// Where the paths of two hailstones cross and whether they do so within the
+/// test area in the future of both.
+fn crossing(a: &Stone, b: &Stone, (min, max): (f64, f64)) -> (Option<Coordsf>, Crossing) {
+    let Some(inter) = a.intersect(b) else {
+        return (None, Crossing::Parallel);
+    };
+
+    let result = match (a.point_in_future(inter), b.point_in_future(inter)) {
+        (true, true) if inter.0 >= min && inter.1 >= min && inter.0 <= max && inter.1 <= max => {
+            Crossing::Inside
+        }
+        (true, true) => Crossing::Outside,
+        (a, b) => Crossing::Past { a: !a, b: !b },
+    };
+    (Some(inter), result)
+}
+
 #[aoc(day24, part1)]
 pub fn solve_part1(input: &[Stone]) -> Result<u32> {
-    let len = input.len();
-    let (min, max) = if len == 5 { (7.0, 27.0) } else { (200000000000000.0, 400000000000000.0) };
+    let area = test_area(input);
 
     let mut count = 0;
     for i in 0..input.len() {
         for j in (i + 1)..input.len() {
-            if let Some(inter) = input[i].intersect(&input[j]) {
-                if !input[i].point_in_future(inter) {
-                    continue;
-                }
-
-                if !input[j].point_in_future(inter) {
-                    continue;
-                }
-
-                if inter.0 >= min && inter.1 >= min && inter.0 <= max && inter.1 <= max {
-                    count += 1;
-                }
+            if crossing(&input[i], &input[j], area).1 == Crossing::Inside {
+                count += 1;
             }
         }
     }
@@ -142,8 +188,9 @@ pub fn solve_part1(input: &[Stone]) -> Result<u32> {
     Ok(count)
 }
 
-#[aoc(day24, part2)]
-pub fn solve_part2(input: &[Stone]) -> Result<i128> {
+/// The linear system for the position and velocity of the rock, reduced to
+/// the identity. The last column holds the negated solution.
+fn eliminate(input: &[Stone]) -> Vec<Vec<f64>> {
     let a = &input[0];
     let b = &input[1];
     let c = &input[2];
@@ -225,12 +272,59 @@ pub fn solve_part2(input: &[Stone]) -> Result<i128> {
         ],
     ];
     gauss_jordan_elimination_generic(&mut m);
+    m
+}
 
+#[aoc(day24, part2)]
+pub fn solve_part2(input: &[Stone]) -> Result<i128> {
+    let m = eliminate(input);
     let result = -(m[0][6] + m[1][6] + m[2][6]);
 
     Ok(result.floor() as i128)
 }
 
+pub fn explain_part1(input: &[Stone]) -> Result<Explanation> {
+    let area = test_area(input);
+    let rows = (0..input.len())
+        .flat_map(|i| (i + 1..input.len()).map(move |j| (i, j)))
+        .map(|(i, j)| {
+            let (inter, result) = crossing(&input[i], &input[j], area);
+            vec![
+                format!("{}-{}", i + 1, j + 1),
+                inter.map_or("-".to_string(), |p| format!("{:.3},{:.3}", p.0, p.1)),
+                result.to_string(),
+            ]
+        })
+        .collect();
+
+    Ok(Explanation::new(
+        &["pair", "crossing", "result"],
+        rows,
+        solve_part1(input)?,
+    ))
+}
+
+pub fn explain_part2(input: &[Stone]) -> Result<Explanation> {
+    let m = eliminate(input);
+    let rows = ["x", "y", "z"]
+        .iter()
+        .enumerate()
+        .map(|(i, axis)| {
+            vec![
+                axis.to_string(),
+                (-m[i][6]).round().to_string(),
+                (-m[i + 3][6]).round().to_string(),
+            ]
+        })
+        .collect();
+
+    Ok(Explanation::new(
+        &["axis", "position", "velocity"],
+        rows,
+        solve_part2(input)?,
+    ))
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -258,4 +352,24 @@ mod test {
         let data = input()?;
         Ok(assert_eq!(47, solve_part2(&data)?))
     }
+
+    #[test]
+    fn part1_explain() -> Result<()> {
+        let data = input()?;
+        let explanation = explain_part1(&data)?;
+        assert_eq!("2", explanation.answer);
+        assert_eq!(vec!["1-2", "14.333,15.333", "inside"], explanation.rows[0]);
+        Ok(assert_eq!(
+            vec!["1-5", "21.444,11.778", "past for A"],
+            explanation.rows[3]
+        ))
+    }
+
+    #[test]
+    fn part2_explain() -> Result<()> {
+        let data = input()?;
+        let explanation = explain_part2(&data)?;
+        assert_eq!("47", explanation.answer);
+        Ok(assert_eq!(vec!["x", "24", "-3"], explanation.rows[0]))
+    }
 }
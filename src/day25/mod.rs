@@ -2,6 +2,8 @@ use anyhow::{Context, Result};
 use itertools::Itertools;
 use std::collections::{HashMap, VecDeque};
 
+use crate::explain::Explanation;
+
 fn parse_line(s: &str) -> Result<(String, Vec<String>)> {
     let mut split = s.split(": ");
     let id = split.next().context("Could not read id")?.to_string();
@@ -64,8 +66,9 @@ fn subgraph_size(map: &HashMap<String, Vec<String>>, start: &str) -> usize {
     visited.len()
 }
 
-#[aoc(day25, part1)]
-pub fn solve_part1(input: &HashMap<String, Vec<String>>) -> Result<usize> {
+/// The graph without the three wires that split it into two components,
+/// together with the ends of one of the removed wires.
+fn cut(input: &HashMap<String, Vec<String>>) -> (HashMap<String, Vec<String>>, (&str, &str)) {
     // determined by looking at a plot of the graph generated by pushing the output
     // of to_digraph() into graphviz and looking at it in inkscape
     let delete = if input.len() == 13 {
@@ -85,8 +88,14 @@ pub fn solve_part1(input: &HashMap<String, Vec<String>>) -> Result<usize> {
             });
     }
 
-    let size1 = subgraph_size(&map, delete[1].0);
-    let size2 = subgraph_size(&map, delete[1].1);
+    (map, delete[1])
+}
+
+#[aoc(day25, part1)]
+pub fn solve_part1(input: &HashMap<String, Vec<String>>) -> Result<usize> {
+    let (map, (a, b)) = cut(input);
+    let size1 = subgraph_size(&map, a);
+    let size2 = subgraph_size(&map, b);
 
     Ok(size1 * size2)
 }
@@ -96,6 +105,28 @@ pub fn solve_part2(_input: &HashMap<String, Vec<String>>) -> Result<u32> {
     Ok(0)
 }
 
+pub fn explain_part1(input: &HashMap<String, Vec<String>>) -> Result<Explanation> {
+    let (map, (a, b)) = cut(input);
+    let rows = [a, b]
+        .iter()
+        .map(|c| vec![c.to_string(), subgraph_size(&map, c).to_string()])
+        .collect();
+
+    Ok(Explanation::new(
+        &["component of", "size"],
+        rows,
+        solve_part1(input)?,
+    ))
+}
+
+pub fn explain_part2(input: &HashMap<String, Vec<String>>) -> Result<Explanation> {
+    Ok(Explanation::new(
+        &["note"],
+        vec![vec!["There is no second puzzle on day 25".to_string()]],
+        solve_part2(input)?,
+    ))
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -126,6 +157,17 @@ frs: qnr lhk lsr"
         Ok(assert_eq!(54, solve_part1(&data)?))
     }
 
+    #[test]
+    fn part1_explain() -> Result<()> {
+        let data = input()?;
+        let explanation = explain_part1(&data)?;
+        assert_eq!("54", explanation.answer);
+        Ok(assert_eq!(
+            vec![vec!["cmg", "9"], vec!["bvb", "6"]],
+            explanation.rows
+        ))
+    }
+
     #[test]
     fn part2_sample() -> Result<()> {
         let data = input()?;
@@ -0,0 +1,79 @@
+use std::fmt::{self, Display};
+
+/// The contribution of every input item to a puzzle answer, e.g. one row per
+/// game or card.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Explanation {
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+    pub answer: String,
+}
+
+impl Explanation {
+    pub fn new<A: ToString>(columns: &[&str], rows: Vec<Vec<String>>, answer: A) -> Self {
+        Explanation {
+            columns: columns.iter().map(|c| c.to_string()).collect(),
+            rows,
+            answer: answer.to_string(),
+        }
+    }
+}
+
+impl Display for Explanation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let widths = (0..self.columns.len())
+            .map(|i| {
+                self.rows
+                    .iter()
+                    .filter_map(|r| r.get(i))
+                    .chain(Some(&self.columns[i]))
+                    .map(|v| v.chars().count())
+                    .max()
+                    .unwrap_or(0)
+            })
+            .collect::<Vec<_>>();
+
+        let line = |values: &[String]| {
+            values
+                .iter()
+                .zip(widths.iter())
+                .map(|(v, w)| format!("{:>w$}", v, w = w))
+                .collect::<Vec<_>>()
+                .join(" | ")
+        };
+
+        writeln!(f, "{}", line(&self.columns))?;
+        let separator = widths.iter().map(|w| "-".repeat(*w)).collect::<Vec<_>>();
+        writeln!(f, "{}", separator.join("-+-"))?;
+        for row in &self.rows {
+            writeln!(f, "{}", line(row))?;
+        }
+        write!(f, "answer: {}", self.answer)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn table() {
+        let explanation = Explanation::new(
+            &["game", "power"],
+            vec![
+                vec!["1".to_string(), "48".to_string()],
+                vec!["12".to_string(), "1560".to_string()],
+            ],
+            1608,
+        );
+
+        assert_eq!(
+            "game | power
+-----+------
+   1 |    48
+  12 |  1560
+answer: 1608",
+            explanation.to_string()
+        );
+    }
+}
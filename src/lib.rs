@@ -3,7 +3,6 @@ extern crate aoc_runner;
 extern crate aoc_runner_derive;
 #[macro_use]
 extern crate lazy_static;
-extern crate memoize;
 extern crate num;
 extern crate pathfinding;
 extern crate regex;
@@ -34,6 +33,11 @@ pub mod day22;
 pub mod day23;
 pub mod day24;
 pub mod day25;
+pub mod explain;
+pub mod simulation;
+pub mod solver;
 pub mod utils;
+#[cfg(feature = "wasm")]
+pub mod wasm;
 
 aoc_lib! { year = 2023 }
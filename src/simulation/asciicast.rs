@@ -0,0 +1,90 @@
+use anyhow::Result;
+use serde_json::json;
+use std::io::Write;
+
+use super::Simulation;
+
+// Clear the screen and move the cursor to the top left corner.
+const CLEAR: &str = "\x1b[2J\x1b[H";
+
+/// Writes up to `frames` steps of a simulation as an asciinema v2 `.cast`
+/// file, `delay` seconds apart. Recording stops early once the simulation
+/// has settled. Returns the number of frames written.
+pub fn record<S, W>(sim: &mut S, frames: usize, delay: f64, output: &mut W) -> Result<usize>
+where
+    S: Simulation,
+    W: Write,
+{
+    let mut rendered = vec![sim.render()];
+    for _ in 0..frames {
+        if !sim.step()? {
+            break;
+        }
+        rendered.push(sim.render());
+    }
+
+    let width = rendered
+        .iter()
+        .flat_map(|f| f.lines().map(|l| l.chars().count()))
+        .max()
+        .unwrap_or(0);
+    let height = rendered
+        .iter()
+        .map(|f| f.lines().count())
+        .max()
+        .unwrap_or(0);
+
+    let header = json!({ "version": 2, "width": width, "height": height });
+    writeln!(output, "{}", header)?;
+
+    for (i, frame) in rendered.iter().enumerate() {
+        let time = (i as f64 * delay * 1_000_000.0).round() / 1_000_000.0;
+        let data = format!("{}{}", CLEAR, frame.replace('\n', "\r\n"));
+        writeln!(output, "{}", json!([time, "o", data]))?;
+    }
+
+    Ok(rendered.len())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use serde_json::Value;
+
+    #[derive(Clone)]
+    struct Grow(usize);
+
+    impl Simulation for Grow {
+        fn step(&mut self) -> Result<bool> {
+            if self.0 == 3 {
+                return Ok(false);
+            }
+            self.0 += 1;
+            Ok(true)
+        }
+
+        fn render(&self) -> String {
+            vec!["#".repeat(self.0); self.0].join("\n")
+        }
+    }
+
+    #[test]
+    fn record_cast() -> Result<()> {
+        let mut output = vec![];
+        let frames = record(&mut Grow(1), 10, 0.5, &mut output)?;
+        assert_eq!(3, frames);
+
+        let cast = String::from_utf8(output)?;
+        let lines = cast
+            .lines()
+            .map(serde_json::from_str::<Value>)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        assert_eq!(4, lines.len());
+        assert_eq!(json!({ "version": 2, "width": 3, "height": 3 }), lines[0]);
+        Ok(assert_eq!(
+            json!([1.0, "o", "\x1b[2J\x1b[H###\r\n###\r\n###"]),
+            lines[3]
+        ))
+    }
+}
@@ -0,0 +1,238 @@
+use anyhow::Result;
+use std::io::{BufRead, Write};
+
+pub mod asciicast;
+
+/// A puzzle state that can be advanced one step at a time and drawn to the
+/// terminal.
+pub trait Simulation {
+    /// Advance the state by one step. Returns `false` if the state did not
+    /// change anymore, i.e. the simulation has settled.
+    fn step(&mut self) -> Result<bool>;
+
+    fn render(&self) -> String;
+}
+
+/// The most snapshots a viewer keeps, no matter how far it is stepped.
+const MAX_CHECKPOINTS: usize = 64;
+
+/// Keeps snapshots of the visited steps so the viewer can go back without
+/// having to re-run the simulation from the start. Every step is kept at
+/// first. Once there are too many, every other snapshot is dropped and the
+/// steps in between are replayed from the snapshot before them when needed.
+pub struct Viewer<S> {
+    /// Snapshots of every `interval`th step, starting with step 0.
+    checkpoints: Vec<S>,
+    interval: usize,
+    current: S,
+    position: usize,
+    /// The step after which the simulation did not change anymore.
+    settled: Option<usize>,
+}
+
+impl<S: Simulation + Clone> Viewer<S> {
+    pub fn new(initial: S) -> Self {
+        Viewer {
+            checkpoints: vec![initial.clone()],
+            interval: 1,
+            current: initial,
+            position: 0,
+            settled: None,
+        }
+    }
+
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    pub fn is_settled(&self) -> bool {
+        self.settled == Some(self.position)
+    }
+
+    pub fn current(&self) -> &S {
+        &self.current
+    }
+
+    pub fn forward(&mut self, steps: usize) -> Result<()> {
+        for _ in 0..steps {
+            if self.is_settled() {
+                break;
+            }
+
+            if !self.current.step()? {
+                self.settled = Some(self.position);
+                break;
+            }
+            self.position += 1;
+
+            if self.position.is_multiple_of(self.interval)
+                && self.position / self.interval == self.checkpoints.len()
+            {
+                self.checkpoints.push(self.current.clone());
+                if self.checkpoints.len() > MAX_CHECKPOINTS {
+                    self.checkpoints = self.checkpoints.drain(..).step_by(2).collect();
+                    self.interval *= 2;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn backward(&mut self, steps: usize) -> Result<()> {
+        let target = self.position.saturating_sub(steps);
+        let checkpoint = target / self.interval;
+        self.current = self.checkpoints[checkpoint].clone();
+        self.position = checkpoint * self.interval;
+
+        self.forward(target - self.position)
+    }
+
+    pub fn jump_to(&mut self, step: usize) -> Result<()> {
+        if step < self.position {
+            self.backward(self.position - step)
+        } else {
+            self.forward(step - self.position)
+        }
+    }
+
+    pub fn render(&self) -> String {
+        let state = if self.is_settled() { " (settled)" } else { "" };
+        format!(
+            "step {}{}\n{}",
+            self.position,
+            state,
+            self.current().render()
+        )
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Command {
+    Forward(usize),
+    Backward(usize),
+    Jump(usize),
+    Quit,
+}
+
+fn parse_command(line: &str) -> Option<Command> {
+    let mut split = line.split_whitespace();
+    let cmd = split.next().unwrap_or("n");
+    let arg = split.next().map(|v| v.parse::<usize>());
+
+    Some(match (cmd, arg) {
+        ("n", None) => Command::Forward(1),
+        ("n", Some(Ok(n))) => Command::Forward(n),
+        ("b", None) => Command::Backward(1),
+        ("b", Some(Ok(n))) => Command::Backward(n),
+        ("j", Some(Ok(n))) => Command::Jump(n),
+        ("q", None) => Command::Quit,
+        _ => None?,
+    })
+}
+
+/// Run the interactive viewer. Reads one command per line from `input`:
+/// `n [N]` steps forward, `b [N]` steps backward, `j N` jumps to step N and
+/// `q` quits. An empty line is the same as `n`.
+pub fn run<S, R, W>(viewer: &mut Viewer<S>, input: R, output: &mut W) -> Result<()>
+where
+    S: Simulation + Clone,
+    R: BufRead,
+    W: Write,
+{
+    writeln!(output, "{}", viewer.render())?;
+
+    for line in input.lines() {
+        match parse_command(&line?) {
+            Some(Command::Forward(n)) => viewer.forward(n)?,
+            Some(Command::Backward(n)) => viewer.backward(n)?,
+            Some(Command::Jump(n)) => viewer.jump_to(n)?,
+            Some(Command::Quit) => break,
+            None => {
+                writeln!(output, "commands: n [N], b [N], j N, q")?;
+                continue;
+            }
+        }
+
+        writeln!(output, "{}", viewer.render())?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[derive(Clone)]
+    struct Countdown(u32);
+
+    impl Simulation for Countdown {
+        fn step(&mut self) -> Result<bool> {
+            if self.0 == 0 {
+                return Ok(false);
+            }
+            self.0 -= 1;
+            Ok(true)
+        }
+
+        fn render(&self) -> String {
+            self.0.to_string()
+        }
+    }
+
+    #[derive(Clone)]
+    struct Broken;
+
+    impl Simulation for Broken {
+        fn step(&mut self) -> Result<bool> {
+            Err(anyhow::anyhow!("broken"))
+        }
+
+        fn render(&self) -> String {
+            String::new()
+        }
+    }
+
+    #[test]
+    fn viewer_steps_back_and_forth() -> Result<()> {
+        let mut viewer = Viewer::new(Countdown(5));
+        viewer.forward(3)?;
+        assert_eq!(2, viewer.current().0);
+        viewer.backward(2)?;
+        assert_eq!(4, viewer.current().0);
+        viewer.jump_to(10)?;
+        assert_eq!(5, viewer.position());
+        Ok(assert!(viewer.is_settled()))
+    }
+
+    #[test]
+    fn viewer_keeps_few_checkpoints() -> Result<()> {
+        let mut viewer = Viewer::new(Countdown(1_000_000));
+        viewer.jump_to(100_000)?;
+        assert!(viewer.checkpoints.len() <= MAX_CHECKPOINTS);
+        assert_eq!(900_000, viewer.current().0);
+
+        viewer.backward(12_345)?;
+        assert_eq!(87_655, viewer.position());
+        assert_eq!(912_345, viewer.current().0);
+        viewer.jump_to(3)?;
+        Ok(assert_eq!(999_997, viewer.current().0))
+    }
+
+    #[test]
+    fn viewer_reports_errors() {
+        let mut viewer = Viewer::new(Broken);
+        assert!(viewer.forward(1).is_err());
+        assert!(run(&mut viewer, "n\n".as_bytes(), &mut vec![]).is_err());
+    }
+
+    #[test]
+    fn run_commands() -> Result<()> {
+        let mut viewer = Viewer::new(Countdown(5));
+        let mut output = vec![];
+        run(&mut viewer, "n 2\nb\nj 4\nq\nn\n".as_bytes(), &mut output)?;
+
+        Ok(assert_eq!(4, viewer.position()))
+    }
+}
@@ -0,0 +1,168 @@
+use anyhow::{anyhow, Result};
+use std::io::BufRead;
+
+use crate::{explain::Explanation, *};
+
+macro_rules! run {
+    ($day:ident, $part:expr, $input:expr) => {{
+        let data = $day::input_generator($input)?;
+        match $part {
+            1 => $day::solve_part1(&data)?.to_string(),
+            2 => $day::solve_part2(&data)?.to_string(),
+            p => Err(anyhow!("There is no part {}", p))?,
+        }
+    }};
+}
+
+macro_rules! stream {
+    ($day:ident, $part:expr, $reader:expr) => {
+        match $part {
+            1 => $day::stream_part1($reader)?.to_string(),
+            2 => $day::stream_part2($reader)?.to_string(),
+            p => Err(anyhow!("There is no part {}", p))?,
+        }
+    };
+}
+
+macro_rules! collect {
+    ($day:ident, $part:expr, $reader:expr) => {{
+        let data = $day::input_reader($reader).collect::<Result<Vec<_>>>()?;
+        match $part {
+            1 => $day::solve_part1(&data)?.to_string(),
+            2 => $day::solve_part2(&data)?.to_string(),
+            p => Err(anyhow!("There is no part {}", p))?,
+        }
+    }};
+}
+
+macro_rules! explain {
+    ($day:ident, $part:expr, $input:expr) => {{
+        let data = $day::input_generator($input)?;
+        match $part {
+            1 => $day::explain_part1(&data)?,
+            2 => $day::explain_part2(&data)?,
+            p => Err(anyhow!("There is no part {}", p))?,
+        }
+    }};
+}
+
+/// Solve the given day and part for the whole puzzle input.
+pub fn solve(day: u32, part: u32, input: &str) -> Result<String> {
+    Ok(match day {
+        1 => run!(day01, part, input),
+        2 => run!(day02, part, input),
+        3 => run!(day03, part, input),
+        4 => run!(day04, part, input),
+        5 => run!(day05, part, input),
+        6 => run!(day06, part, input),
+        7 => run!(day07, part, input),
+        8 => run!(day08, part, input),
+        9 => run!(day09, part, input),
+        10 => run!(day10, part, input),
+        11 => run!(day11, part, input),
+        12 => run!(day12, part, input),
+        13 => run!(day13, part, input),
+        14 => run!(day14, part, input),
+        15 => run!(day15, part, input),
+        16 => run!(day16, part, input),
+        17 => run!(day17, part, input),
+        18 => run!(day18, part, input),
+        19 => run!(day19, part, input),
+        20 => run!(day20, part, input),
+        21 => run!(day21, part, input),
+        22 => run!(day22, part, input),
+        23 => run!(day23, part, input),
+        24 => run!(day24, part, input),
+        25 => run!(day25, part, input),
+        d => Err(anyhow!("There is no day {}", d))?,
+    })
+}
+
+/// Solve the given day and part reading the puzzle input from `reader`.
+///
+/// Days 1, 2, 4, 9, 12, 15 and 18 only need one line at a time and are solved
+/// while reading, so their memory use does not grow with the input. Days 7,
+/// 22 and 24 parse line by line but keep all parsed items in memory, and all
+/// remaining days read the whole input first.
+pub fn solve_reader<R: BufRead>(day: u32, part: u32, mut reader: R) -> Result<String> {
+    Ok(match day {
+        1 => stream!(day01, part, reader),
+        2 => stream!(day02, part, reader),
+        4 => stream!(day04, part, reader),
+        7 => collect!(day07, part, reader),
+        9 => stream!(day09, part, reader),
+        12 => stream!(day12, part, reader),
+        15 => stream!(day15, part, reader),
+        18 => stream!(day18, part, reader),
+        22 => collect!(day22, part, reader),
+        24 => collect!(day24, part, reader),
+        d => {
+            let mut input = String::new();
+            reader.read_to_string(&mut input)?;
+            solve(d, part, &input)?
+        }
+    })
+}
+
+/// List how every item of the input contributes to the answer.
+pub fn explain(day: u32, part: u32, input: &str) -> Result<Explanation> {
+    Ok(match day {
+        1 => explain!(day01, part, input),
+        2 => explain!(day02, part, input),
+        3 => explain!(day03, part, input),
+        4 => explain!(day04, part, input),
+        5 => explain!(day05, part, input),
+        6 => explain!(day06, part, input),
+        7 => explain!(day07, part, input),
+        8 => explain!(day08, part, input),
+        9 => explain!(day09, part, input),
+        10 => explain!(day10, part, input),
+        11 => explain!(day11, part, input),
+        12 => explain!(day12, part, input),
+        13 => explain!(day13, part, input),
+        14 => explain!(day14, part, input),
+        15 => explain!(day15, part, input),
+        16 => explain!(day16, part, input),
+        17 => explain!(day17, part, input),
+        18 => explain!(day18, part, input),
+        19 => explain!(day19, part, input),
+        20 => explain!(day20, part, input),
+        21 => explain!(day21, part, input),
+        22 => explain!(day22, part, input),
+        23 => explain!(day23, part, input),
+        24 => explain!(day24, part, input),
+        25 => explain!(day25, part, input),
+        d => Err(anyhow!("There is no day {}", d))?,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn sample() -> &'static str {
+        "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
+Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19
+Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1
+Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
+Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
+Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11"
+    }
+
+    #[test]
+    fn solve_and_stream_agree() -> Result<()> {
+        let solved = solve(4, 2, sample())?;
+        let streamed = solve_reader(4, 2, sample().as_bytes())?;
+        assert_eq!("30", solved);
+        Ok(assert_eq!(solved, streamed))
+    }
+
+    #[test]
+    fn explain_and_solve_agree() -> Result<()> {
+        assert!(explain(26, 1, sample()).is_err());
+        Ok(assert_eq!(
+            solve(4, 2, sample())?,
+            explain(4, 2, sample())?.answer
+        ))
+    }
+}
@@ -1,3 +1,5 @@
+use anyhow::Result;
+use std::io::BufRead;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -5,3 +7,17 @@ pub enum AocError {
     #[error("Generic error")]
     GenericError,
 }
+
+/// Lazily parse the non-empty lines of `reader`, one item per line. Only the
+/// current line is kept in memory.
+pub fn read_lines<R, T, F>(reader: R, parse: F) -> impl Iterator<Item = Result<T>>
+where
+    R: BufRead,
+    F: Fn(&str) -> Result<T>,
+{
+    reader.lines().filter_map(move |l| match l {
+        Ok(l) if l.trim().is_empty() => None,
+        Ok(l) => Some(parse(l.trim_end())),
+        Err(e) => Some(Err(e.into())),
+    })
+}
@@ -0,0 +1,94 @@
+use std::{ptr, slice, str};
+
+use crate::solver;
+
+/// Solve the given day and part. Errors are turned into their message so
+/// they can be passed on to JS.
+pub fn solve(day: u32, part: u32, input: &str) -> Result<String, String> {
+    solver::solve(day, part, input).map_err(|e| format!("{:#}", e))
+}
+
+// Without wasm-bindgen strings have to be passed through the module's linear
+// memory. JS allocates a buffer with `alloc`, writes the UTF-8 encoded input
+// to it and calls `solve`. The result buffer starts with the length of the
+// message as little endian u32, followed by 1 for Ok or 0 for Err and then the
+// message itself. JS has to release both buffers with `dealloc`.
+
+const HEADER: usize = 5;
+
+#[no_mangle]
+pub extern "C" fn alloc(len: usize) -> *mut u8 {
+    into_raw(vec![0; len])
+}
+
+// Boxed slices have exactly the requested size, so `dealloc` can rebuild
+// them from the pointer and the length alone.
+fn into_raw(buf: Vec<u8>) -> *mut u8 {
+    Box::into_raw(buf.into_boxed_slice()) as *mut u8
+}
+
+/// # Safety
+///
+/// `ptr` and `len` must describe a buffer returned by `alloc` or `solve`.
+#[no_mangle]
+pub unsafe extern "C" fn dealloc(ptr: *mut u8, len: usize) {
+    drop(Box::from_raw(ptr::slice_from_raw_parts_mut(ptr, len)));
+}
+
+fn encode(result: Result<String, String>) -> Vec<u8> {
+    let (ok, message) = match result {
+        Ok(m) => (1, m),
+        Err(m) => (0, m),
+    };
+
+    let mut buf = Vec::with_capacity(HEADER + message.len());
+    buf.extend_from_slice(&(message.len() as u32).to_le_bytes());
+    buf.push(ok);
+    buf.extend_from_slice(message.as_bytes());
+    buf
+}
+
+/// # Safety
+///
+/// `ptr` must point to `len` initialized bytes, e.g. a buffer returned by
+/// `alloc`.
+#[export_name = "solve"]
+pub unsafe extern "C" fn solve_raw(day: u32, part: u32, ptr: *const u8, len: usize) -> *mut u8 {
+    let input = slice::from_raw_parts(ptr, len);
+    let result = match str::from_utf8(input) {
+        Ok(input) => solve(day, part, input),
+        Err(e) => Err(e.to_string()),
+    };
+
+    into_raw(encode(result))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn solve_day09() {
+        let input = "0 3 6 9 12 15\n1 3 6 10 15 21\n10 13 16 21 30 45";
+        assert_eq!(Ok("114".to_string()), solve(9, 1, input));
+        assert!(solve(9, 3, input).is_err());
+    }
+
+    #[test]
+    fn solve_raw_roundtrip() {
+        let input = "0 3 6 9 12 15\n1 3 6 10 15 21\n10 13 16 21 30 45";
+
+        unsafe {
+            let ptr = alloc(input.len());
+            ptr.copy_from_nonoverlapping(input.as_ptr(), input.len());
+            let result = solve_raw(9, 2, ptr, input.len());
+            dealloc(ptr, input.len());
+
+            let len = u32::from_le_bytes(*(result as *const [u8; 4])) as usize;
+            let buf = slice::from_raw_parts(result, HEADER + len);
+            assert_eq!(1, buf[4]);
+            assert_eq!(b"2", &buf[HEADER..]);
+            dealloc(result, HEADER + len);
+        }
+    }
+}